@@ -33,7 +33,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     {
         let name = "G1Affine";
         let a = G1Affine::generator();
-        let s = Scalar::from_raw([1, 2, 3, 4]);
+        let s = Scalar::from_raw([1, 2, 3, 4]).unwrap();
         let compressed = [0u8; 48];
         let uncompressed = [0u8; 96];
         c.bench_function(&format!("{} check on curve", name), move |b| {
@@ -63,7 +63,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         let name = "G1Projective";
         let a = G1Projective::GENERATOR;
         let a_affine = G1Affine::generator();
-        let s = Scalar::from_raw([1, 2, 3, 4]);
+        let s = Scalar::from_raw([1, 2, 3, 4]).unwrap();
 
         const N: usize = 10000;
         let v = vec![G1Projective::GENERATOR; N];
@@ -102,7 +102,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     {
         let name = "G2Affine";
         let a = G2Affine::generator();
-        let s = Scalar::from_raw([1, 2, 3, 4]);
+        let s = Scalar::from_raw([1, 2, 3, 4]).unwrap();
         let compressed = [0u8; 96];
         let uncompressed = [0u8; 192];
         c.bench_function(&format!("{} check on curve", name), move |b| {
@@ -132,7 +132,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         let name = "G2Projective";
         let a = G2Projective::GENERATOR;
         let a_affine = G2Affine::generator();
-        let s = Scalar::from_raw([1, 2, 3, 4]);
+        let s = Scalar::from_raw([1, 2, 3, 4]).unwrap();
 
         const N: usize = 10000;
         let v = vec![G2Projective::GENERATOR; N];
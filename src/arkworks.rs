@@ -0,0 +1,129 @@
+//! This module describes the little-endian encoding of group elements used
+//! by the generic short Weierstrass serialization of arkworks, which is
+//! provided alongside the big-endian encoding described in
+//! [`notes::serialization`](crate::notes::serialization) by
+//! `to_compressed_le`, `to_uncompressed_le`, `from_compressed_le` and
+//! `from_uncompressed_le` on [`G1Affine`](crate::G1Affine) and
+//! [`G2Affine`](crate::G2Affine), and `to_bytes_le` and `from_bytes_le` on
+//! [`Gt`](crate::Gt).
+//!
+//! This is the encoding of `ark-bls12-381` 0.3, which serializes points
+//! through `ark-ec` 0.3 and `ark-serialize` 0.3. From 0.4, `ark-bls12-381`
+//! serializes points in the big-endian encoding instead, which
+//! `to_compressed` and `to_uncompressed` already produce.
+//!
+//! * A base field element is encoded as 48 bytes in little-endian order.
+//! * An element $c_0 + c_1 u$ of $\mathbb{F}_{p^2}$ is encoded as $c_0$
+//!   followed by $c_1$.
+//! * An element of $\mathbb{G}_T$ is encoded as its twelve base field
+//!   coefficients in the same order as the big-endian encoding, but each in
+//!   little-endian order.
+//! * A compressed point is encoded as its $x$-coordinate, and an uncompressed
+//!   point as its $x$-coordinate followed by its $y$-coordinate. The point at
+//!   infinity is encoded as $x = 0$ when compressed and as $(0, 1)$ when
+//!   uncompressed.
+//! * The two most significant bits of the last byte are flags. The second
+//!   most significant bit is set if the point is the point at infinity. In
+//!   the compressed encoding, the most significant bit is set if the point is
+//!   not the point at infinity and its $y$-coordinate is the
+//!   lexicographically largest of $\pm y$. The uncompressed encoding never
+//!   sets it. Setting both is invalid.
+//!
+//! [`Scalar::to_le_bytes`](crate::Scalar::to_le_bytes) and
+//! [`Scalar::from_le_bytes`](crate::Scalar::from_le_bytes) already match the
+//! arkworks encoding of scalars.
+
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+use crate::fp::Fp;
+use crate::fp2::Fp2;
+
+/// Whether decoding should check that the element is valid, rather than only
+/// that it is canonically encoded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Validate {
+    /// Check that points are on the curve and that elements are in the prime
+    /// order subgroup.
+    Yes,
+    /// Skip the checks above. **This is dangerous to use unless you trust the
+    /// bytes you are reading; otherwise, API invariants may be broken.**
+    No,
+}
+
+impl Validate {
+    /// Returns a `Choice` which is set if validation should be performed.
+    pub(crate) fn choice(self) -> Choice {
+        Choice::from((self == Validate::Yes) as u8)
+    }
+}
+
+/// The flag set if the $y$-coordinate is the lexicographically largest.
+const NEGATIVE_FLAG: u8 = 1 << 7;
+/// The flag set for the point at infinity.
+const INFINITY_FLAG: u8 = 1 << 6;
+/// The mask of both flags.
+const FLAGS_MASK: u8 = NEGATIVE_FLAG | INFINITY_FLAG;
+
+/// Reverses each 48-byte chunk in `bytes`, converting a sequence of big-endian
+/// base field elements to little-endian or back.
+pub(crate) fn reverse_fp_chunks(bytes: &mut [u8]) {
+    for chunk in bytes.chunks_exact_mut(48) {
+        chunk.reverse();
+    }
+}
+
+/// Decodes a little-endian base field element from `bytes`, which must be
+/// 48 bytes with any flags already masked away.
+pub(crate) fn fp_from_le_bytes(bytes: &[u8]) -> CtOption<Fp> {
+    let mut tmp = [0u8; 48];
+    tmp.copy_from_slice(bytes);
+    tmp.reverse();
+    Fp::from_bytes(&tmp)
+}
+
+/// Decodes a little-endian $\mathbb{F}_{p^2}$ element from `bytes`, which must
+/// be 96 bytes with any flags already masked away.
+pub(crate) fn fp2_from_le_bytes(bytes: &[u8]) -> CtOption<Fp2> {
+    let c0 = fp_from_le_bytes(&bytes[..48]);
+    let c1 = fp_from_le_bytes(&bytes[48..]);
+
+    c0.and_then(|c0| c1.map(|c1| Fp2 { c0, c1 }))
+}
+
+/// Computes the flags byte for a point.
+pub(crate) fn encode_flags(infinity: Choice, y_is_largest: Choice) -> u8 {
+    let negative = u8::conditional_select(&0, &NEGATIVE_FLAG, y_is_largest);
+    u8::conditional_select(&negative, &INFINITY_FLAG, infinity)
+}
+
+/// Removes the flags from the last byte of `bytes`, returning whether the
+/// flags are valid, whether the infinity flag was set, and whether the
+/// $y$-coordinate is the lexicographically largest.
+pub(crate) fn decode_flags(bytes: &mut [u8]) -> (Choice, Choice, Choice) {
+    let last = bytes.len() - 1;
+    let flags = bytes[last] & FLAGS_MASK;
+    bytes[last] &= !FLAGS_MASK;
+
+    (
+        !flags.ct_eq(&FLAGS_MASK),
+        flags.ct_eq(&INFINITY_FLAG),
+        flags.ct_eq(&NEGATIVE_FLAG),
+    )
+}
+
+#[test]
+fn test_flags() {
+    for (infinity, largest) in [(0u8, 0u8), (0, 1), (1, 0), (1, 1)] {
+        let mut bytes = [0u8; 48];
+        bytes[47] = 0x3f | encode_flags(Choice::from(infinity), Choice::from(largest));
+        let (valid, inf, neg) = decode_flags(&mut bytes);
+        assert!(bool::from(valid));
+        assert_eq!(inf.unwrap_u8(), infinity);
+        assert_eq!(neg.unwrap_u8(), largest & !infinity);
+        assert_eq!(bytes[47], 0x3f);
+    }
+
+    let mut bytes = [0u8; 48];
+    bytes[47] = FLAGS_MASK;
+    assert!(!bool::from(decode_flags(&mut bytes).0));
+}
@@ -0,0 +1,527 @@
+//! Montgomery multiplication for x86_64 using the MULX, ADCX and ADOX
+//! instructions of the BMI2 and ADX extensions, enabled by the `asm` feature.
+//!
+//! Support is detected at runtime, with callers falling back to the portable
+//! arithmetic on older CPUs.
+
+use core::arch::asm;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Returns whether the CPU supports BMI2 and ADX, caching the result.
+#[inline]
+pub(crate) fn has_mulx_adx() -> bool {
+    /// 0 when not yet detected, 1 when unsupported and 2 when supported
+    static SUPPORT: AtomicU8 = AtomicU8::new(0);
+
+    if cfg!(all(target_feature = "bmi2", target_feature = "adx")) {
+        return true;
+    }
+    match SUPPORT.load(Ordering::Relaxed) {
+        0 => {
+            let supported = detect();
+            SUPPORT.store(1 + supported as u8, Ordering::Relaxed);
+            supported
+        }
+        support => support == 2,
+    }
+}
+
+#[cold]
+fn detect() -> bool {
+    use core::arch::x86_64::{__cpuid, __cpuid_count};
+
+    if __cpuid(0).eax < 7 {
+        return false;
+    }
+    // Structured extended features: BMI2 is bit 8 and ADX bit 19 of EBX
+    let ebx = __cpuid_count(7, 0).ebx;
+    ebx & (1 << 8) != 0 && ebx & (1 << 19) != 0
+}
+
+/// Montgomery multiplication of `a` and `b` modulo the 6-limb modulus held
+/// in the first limbs of `m`, followed by `-m^-1 mod 2^64`. While `b` may be
+/// any value, `a` must be less than `m`, and the result is then less than
+/// `2m` and needs a final conditional subtraction.
+///
+/// # Safety
+///
+/// The CPU must support BMI2 and ADX, see [`has_mulx_adx`]. The modulus must
+/// be odd and its top limb below `2^63`.
+#[inline]
+pub(crate) unsafe fn montgomery_mul_6(a: &[u64; 6], b: &[u64; 6], m: &[u64; 7]) -> [u64; 6] {
+    let (r0, r1, r2, r3, r4, r6): (u64, u64, u64, u64, u64, u64);
+    // Interleaved CIOS multiplication. ADCX and ADOX keep two independent
+    // carry chains for the low and high halves of each MULX product, and the
+    // accumulator registers rotate so that no shifting is needed.
+    asm!(
+        // t += a * b[0]
+        "mov rdx, qword ptr [{b} + 0]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 32]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 40]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t6}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t0}",
+        "imul rdx, qword ptr [{m} + 48]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 32]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 40]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t6}, {lo}",
+        // t += a * b[1]
+        "mov rdx, qword ptr [{b} + 8]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 32]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 40]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t0}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t1}",
+        "imul rdx, qword ptr [{m} + 48]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 32]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 40]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t0}, {lo}",
+        // t += a * b[2]
+        "mov rdx, qword ptr [{b} + 16]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 32]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 40]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t1}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t2}",
+        "imul rdx, qword ptr [{m} + 48]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 32]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 40]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t1}, {lo}",
+        // t += a * b[3]
+        "mov rdx, qword ptr [{b} + 24]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 32]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 40]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t2}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t3}",
+        "imul rdx, qword ptr [{m} + 48]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 32]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 40]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t2}, {lo}",
+        // t += a * b[4]
+        "mov rdx, qword ptr [{b} + 32]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 32]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 40]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t3}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t4}",
+        "imul rdx, qword ptr [{m} + 48]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t4}, {lo}",
+        "adox {t5}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 32]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 40]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t3}, {lo}",
+        // t += a * b[5]
+        "mov rdx, qword ptr [{b} + 40]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 32]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 40]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t4}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t5}",
+        "imul rdx, qword ptr [{m} + 48]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t5}, {lo}",
+        "adox {t6}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t6}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 32]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 40]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t4}, {lo}",
+        a = in(reg) a.as_ptr(),
+        b = in(reg) b.as_ptr(),
+        m = in(reg) m.as_ptr(),
+        t0 = inout(reg) 0u64 => r0,
+        t1 = inout(reg) 0u64 => r1,
+        t2 = inout(reg) 0u64 => r2,
+        t3 = inout(reg) 0u64 => r3,
+        t4 = inout(reg) 0u64 => r4,
+        t5 = inout(reg) 0u64 => _,
+        t6 = inout(reg) 0u64 => r6,
+        lo = out(reg) _,
+        hi = out(reg) _,
+        out("rdx") _,
+        options(pure, readonly, nostack)
+    );
+    [r6, r0, r1, r2, r3, r4]
+}
+
+/// Montgomery multiplication of `a` and `b` modulo the 4-limb modulus held
+/// in the first limbs of `m`, followed by `-m^-1 mod 2^64`. While `b` may be
+/// any value, `a` must be less than `m`, and the result is then less than
+/// `2m` and needs a final conditional subtraction.
+///
+/// # Safety
+///
+/// The CPU must support BMI2 and ADX, see [`has_mulx_adx`]. The modulus must
+/// be odd and its top limb below `2^63`.
+#[inline]
+pub(crate) unsafe fn montgomery_mul_4(a: &[u64; 4], b: &[u64; 4], m: &[u64; 5]) -> [u64; 4] {
+    let (r0, r1, r2, r4): (u64, u64, u64, u64);
+    // Interleaved CIOS multiplication. ADCX and ADOX keep two independent
+    // carry chains for the low and high halves of each MULX product, and the
+    // accumulator registers rotate so that no shifting is needed.
+    asm!(
+        // t += a * b[0]
+        "mov rdx, qword ptr [{b} + 0]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t4}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t0}",
+        "imul rdx, qword ptr [{m} + 32]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t4}, {lo}",
+        // t += a * b[1]
+        "mov rdx, qword ptr [{b} + 8]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t4}, {lo}",
+        "adox {t0}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t0}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t1}",
+        "imul rdx, qword ptr [{m} + 32]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t4}, {lo}",
+        "adox {t0}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t0}, {lo}",
+        // t += a * b[2]
+        "mov rdx, qword ptr [{b} + 16]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t4}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t1}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t2}",
+        "imul rdx, qword ptr [{m} + 32]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t2}, {lo}",
+        "adox {t3}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t4}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t1}, {lo}",
+        // t += a * b[3]
+        "mov rdx, qword ptr [{b} + 24]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 0]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 8]",
+        "adcx {t4}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 16]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{a} + 24]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t2}, {lo}",
+        // t = (t + m * k) / 2^64 with k = t[0] * INV
+        "mov rdx, {t3}",
+        "imul rdx, qword ptr [{m} + 32]",
+        "xor {lo:e}, {lo:e}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 0]",
+        "adcx {t3}, {lo}",
+        "adox {t4}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 8]",
+        "adcx {t4}, {lo}",
+        "adox {t0}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 16]",
+        "adcx {t0}, {lo}",
+        "adox {t1}, {hi}",
+        "mulx {hi}, {lo}, qword ptr [{m} + 24]",
+        "adcx {t1}, {lo}",
+        "adox {t2}, {hi}",
+        "mov {lo:e}, 0",
+        "adcx {t2}, {lo}",
+        a = in(reg) a.as_ptr(),
+        b = in(reg) b.as_ptr(),
+        m = in(reg) m.as_ptr(),
+        t0 = inout(reg) 0u64 => r0,
+        t1 = inout(reg) 0u64 => r1,
+        t2 = inout(reg) 0u64 => r2,
+        t3 = inout(reg) 0u64 => _,
+        t4 = inout(reg) 0u64 => r4,
+        lo = out(reg) _,
+        hi = out(reg) _,
+        out("rdx") _,
+        options(pure, readonly, nostack)
+    );
+    [r4, r0, r1, r2]
+}
@@ -0,0 +1,652 @@
+//! This module provides radix-2 number theoretic transforms over the scalar
+//! field, which has a multiplicative subgroup of every order 2^k for
+//! k <= 32.
+//!
+//! An [`EvaluationDomain`] of size n = 2^k is the subgroup generated by the
+//! primitive n-th root of unity ω returned by [`Scalar::root_of_unity`]. The
+//! forward transform maps the coefficients `a_0, ..., a_{n - 1}` of a
+//! polynomial to its evaluations at `ω^0, ..., ω^{n - 1}`, and the inverse
+//! transform maps them back. Both are in place and take and return values in
+//! natural order. The coset transforms evaluate over the coset `gH` instead,
+//! with `g` the multiplicative generator.
+//!
+//! The same transforms apply in the exponent to points of G1 and G2, for
+//! example to convert a trusted setup from the monomial to the Lagrange basis.
+//!
+//! With the `std` feature, large transforms are spread across the available
+//! threads.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter;
+use core::ops::{Add, Mul, Sub};
+
+use ff::{Field, PrimeField};
+
+use crate::Scalar;
+#[cfg(feature = "groups")]
+use crate::{G1Affine, G1Projective, G2Affine, G2Projective};
+
+/// A multiplicative subgroup of the scalar field of power of two order, with
+/// precomputed twiddle factors for number theoretic transforms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvaluationDomain {
+    log_size: u32,
+    size: usize,
+    /// The primitive root of unity ω generating the domain
+    generator: Scalar,
+    generator_inv: Scalar,
+    size_inv: Scalar,
+    /// ω^i for i < size / 2
+    twiddles: Vec<Scalar>,
+}
+
+impl EvaluationDomain {
+    /// Returns the smallest domain with at least `min_size` elements, or
+    /// `None` if that exceeds 2^32.
+    pub fn new(min_size: usize) -> Option<Self> {
+        let size = min_size.checked_next_power_of_two()?;
+        Self::from_log_size(size.trailing_zeros())
+    }
+
+    /// Returns the domain of size 2^`log_size`, or `None` if `log_size`
+    /// exceeds 32.
+    pub fn from_log_size(log_size: u32) -> Option<Self> {
+        let generator = Scalar::root_of_unity(log_size)?;
+        let size = 1usize.checked_shl(log_size)?;
+        let twiddles = iter::successors(Some(Scalar::ONE), |w| Some(w * generator))
+            .take(size / 2)
+            .collect();
+
+        Some(Self {
+            log_size,
+            size,
+            generator,
+            generator_inv: Scalar::root_of_unity_inv(log_size)?,
+            size_inv: Scalar::from(size as u64).invert().unwrap(),
+            twiddles,
+        })
+    }
+
+    /// Returns the number of elements n of the domain.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns log_2 of the number of elements of the domain.
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    /// Returns the primitive n-th root of unity ω generating the domain.
+    pub fn generator(&self) -> Scalar {
+        self.generator
+    }
+
+    /// Returns ω^-1.
+    pub fn generator_inv(&self) -> Scalar {
+        self.generator_inv
+    }
+
+    /// Returns n^-1.
+    pub fn size_inv(&self) -> Scalar {
+        self.size_inv
+    }
+
+    /// Returns the shift g of the coset gH used by the coset transforms,
+    /// which is the multiplicative generator of the field.
+    pub fn coset_shift(&self) -> Scalar {
+        Scalar::MULTIPLICATIVE_GENERATOR
+    }
+
+    /// Returns the `index`th element ω^`index` of the domain.
+    pub fn element(&self, index: usize) -> Scalar {
+        let index = index % self.size;
+        match self.twiddles.get(index) {
+            Some(w) => *w,
+            // ω^(n / 2) = -1
+            None if self.size > 1 => -self.twiddles[index - self.size / 2],
+            None => Scalar::ONE,
+        }
+    }
+
+    /// Iterates over the elements ω^0, ..., ω^(n - 1) of the domain.
+    pub fn elements(&self) -> impl Iterator<Item = Scalar> + '_ {
+        (0..self.size).map(move |i| self.element(i))
+    }
+
+    /// Evaluates the vanishing polynomial x^n - 1 of the domain at `x`.
+    pub fn evaluate_vanishing_polynomial(&self, x: &Scalar) -> Scalar {
+        let mut t = *x;
+        for _ in 0..self.log_size {
+            t = t.square();
+        }
+        t - Scalar::ONE
+    }
+
+    /// Replaces the coefficients of a polynomial with its evaluations over
+    /// the domain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` does not have exactly n elements.
+    pub fn fft(&self, values: &mut [Scalar]) {
+        self.check_length(values);
+        self.ntt(values);
+    }
+
+    /// Replaces the evaluations of a polynomial over the domain with its
+    /// coefficients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` does not have exactly n elements.
+    pub fn ifft(&self, values: &mut [Scalar]) {
+        self.check_length(values);
+        self.intt(values);
+    }
+
+    /// Replaces the coefficients of a polynomial with its evaluations over
+    /// the coset gH.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` does not have exactly n elements.
+    pub fn coset_fft(&self, values: &mut [Scalar]) {
+        self.check_length(values);
+        distribute_powers(values, Scalar::MULTIPLICATIVE_GENERATOR);
+        self.ntt(values);
+    }
+
+    /// Replaces the evaluations of a polynomial over the coset gH with its
+    /// coefficients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` does not have exactly n elements.
+    pub fn coset_ifft(&self, values: &mut [Scalar]) {
+        self.ifft(values);
+        distribute_powers(values, Scalar::MULTIPLICATIVE_GENERATOR.invert().unwrap());
+    }
+
+    /// Transforms the coefficients `a_i` of a polynomial p given in the
+    /// exponent as the points `a_i G` to its evaluations `p(ω^i) G` over the
+    /// domain, in affine form.
+    ///
+    /// This takes about (n / 2) log_2 n point multiplications.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` does not have exactly n elements.
+    #[cfg(feature = "groups")]
+    pub fn fft_g1(&self, points: &[G1Projective]) -> Vec<G1Affine> {
+        self.check_length(points);
+        let mut values = points.to_vec();
+        self.ntt(&mut values);
+        let mut result = vec![G1Affine::identity(); self.size];
+        G1Projective::batch_normalize(&values, &mut result);
+        result
+    }
+
+    /// Transforms the evaluations `p(ω^i) G` of a polynomial p over the
+    /// domain in the exponent to its coefficients `a_i G`, in affine form.
+    ///
+    /// Applied to the n points `τ^i G` of a monomial basis setup, this gives
+    /// the points `L_i(τ) G` of the Lagrange basis setup, where L_i are the
+    /// Lagrange basis polynomials of the domain.
+    ///
+    /// This takes about (n / 2) log_2 n + n point multiplications.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` does not have exactly n elements.
+    #[cfg(feature = "groups")]
+    pub fn ifft_g1(&self, points: &[G1Projective]) -> Vec<G1Affine> {
+        self.check_length(points);
+        let mut values = points.to_vec();
+        self.intt(&mut values);
+        let mut result = vec![G1Affine::identity(); self.size];
+        G1Projective::batch_normalize(&values, &mut result);
+        result
+    }
+
+    /// The G2 equivalent of [`fft_g1`](Self::fft_g1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` does not have exactly n elements.
+    #[cfg(feature = "groups")]
+    pub fn fft_g2(&self, points: &[G2Projective]) -> Vec<G2Affine> {
+        self.check_length(points);
+        let mut values = points.to_vec();
+        self.ntt(&mut values);
+        let mut result = vec![G2Affine::identity(); self.size];
+        G2Projective::batch_normalize(&values, &mut result);
+        result
+    }
+
+    /// The G2 equivalent of [`ifft_g1`](Self::ifft_g1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` does not have exactly n elements.
+    #[cfg(feature = "groups")]
+    pub fn ifft_g2(&self, points: &[G2Projective]) -> Vec<G2Affine> {
+        self.check_length(points);
+        let mut values = points.to_vec();
+        self.intt(&mut values);
+        let mut result = vec![G2Affine::identity(); self.size];
+        G2Projective::batch_normalize(&values, &mut result);
+        result
+    }
+
+    /// Returns the values L_0(x), ..., L_(n - 1)(x) of the Lagrange basis
+    /// polynomials of the domain at `x`, where L_i is one at ω^i and zero at
+    /// the other elements.
+    ///
+    /// This is variable time with respect to whether `x` is in the domain.
+    pub fn lagrange_coefficients(&self, x: &Scalar) -> Vec<Scalar> {
+        // L_i(x) = (x^n - 1) / n * ω^i / (x - ω^i)
+        let mut denominators = self.elements().map(|w| x - w).collect::<Vec<_>>();
+        if let Some(i) = denominators.iter().position(|d| bool::from(d.is_zero())) {
+            let mut coeffs = vec![Scalar::ZERO; self.size];
+            coeffs[i] = Scalar::ONE;
+            return coeffs;
+        }
+        Scalar::batch_invert_with_scratch(&mut denominators, &mut vec![Scalar::ZERO; self.size]);
+
+        let scale = self.evaluate_vanishing_polynomial(x) * self.size_inv;
+        for (d, w) in denominators.iter_mut().zip(self.elements()) {
+            *d *= scale * w;
+        }
+        denominators
+    }
+
+    /// Evaluates the polynomial of degree less than n with the values
+    /// `evaluations` over the domain at `x`, without interpolating it.
+    ///
+    /// This is variable time with respect to whether `x` is in the domain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `evaluations` does not have exactly n elements.
+    pub fn evaluate_barycentric(&self, evaluations: &[Scalar], x: &Scalar) -> Scalar {
+        self.check_length(evaluations);
+        self.lagrange_coefficients(x)
+            .iter()
+            .zip(evaluations)
+            .fold(Scalar::ZERO, |acc, (l, e)| acc + l * e)
+    }
+
+    fn check_length<T>(&self, values: &[T]) {
+        assert_eq!(
+            values.len(),
+            self.size,
+            "the input length must equal the domain size"
+        );
+    }
+
+    /// The forward transform, in natural order.
+    fn ntt<T: TransformElement>(&self, values: &mut [T]) {
+        #[cfg(feature = "std")]
+        if self.size >= T::PARALLEL_MIN_SIZE {
+            let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+            if threads > 1 {
+                radix2_parallel(values, &self.twiddles, threads);
+                return;
+            }
+        }
+        radix2(values, &self.twiddles);
+    }
+
+    /// The inverse transform, in natural order.
+    fn intt<T: TransformElement>(&self, values: &mut [T]) {
+        // The inverse transform is the forward transform with ω^-1, which
+        // permutes the outputs by i -> -i mod n.
+        self.ntt(values);
+        values[1..].reverse();
+        for v in values.iter_mut() {
+            *v = *v * self.size_inv;
+        }
+    }
+}
+
+/// Permutes `values` by reversing the bits of each index, which is how the
+/// radix-2 transforms reorder their inputs.
+///
+/// # Panics
+///
+/// Panics if the length of `values` is not a power of two.
+pub fn bit_reverse_permutation<T>(values: &mut [T]) {
+    let n = values.len();
+    assert!(n.is_power_of_two(), "the length must be a power of two");
+    if n == 1 {
+        return;
+    }
+
+    let shift = usize::BITS - n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> shift;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+/// Multiplies `values[i]` by `g^i`.
+fn distribute_powers<T: TransformElement>(values: &mut [T], g: Scalar) {
+    let mut power = Scalar::ONE;
+    for v in values.iter_mut() {
+        *v = *v * power;
+        power *= g;
+    }
+}
+
+/// Values that the transforms apply to: the scalars themselves, and points
+/// of a group of order r, which are transformed "in the exponent".
+trait TransformElement:
+    Copy + Send + Sync + Add<Output = Self> + Sub<Output = Self> + Mul<Scalar, Output = Self>
+{
+    /// The smallest transform spread across threads. Each butterfly costs a
+    /// multiplication by a scalar, so this falls as that gets more expensive.
+    #[cfg(feature = "std")]
+    const PARALLEL_MIN_SIZE: usize;
+}
+
+impl TransformElement for Scalar {
+    #[cfg(feature = "std")]
+    const PARALLEL_MIN_SIZE: usize = 1 << 14;
+}
+
+#[cfg(feature = "groups")]
+impl TransformElement for G1Projective {
+    #[cfg(feature = "std")]
+    const PARALLEL_MIN_SIZE: usize = 1 << 6;
+}
+
+#[cfg(feature = "groups")]
+impl TransformElement for G2Projective {
+    #[cfg(feature = "std")]
+    const PARALLEL_MIN_SIZE: usize = 1 << 5;
+}
+
+/// Applies the butterflies of one stage to the matching halves `lo` and `hi`
+/// of a block, whose first butterfly is the `offset`th of the block.
+fn butterflies<T: TransformElement>(
+    lo: &mut [T],
+    hi: &mut [T],
+    twiddles: &[Scalar],
+    offset: usize,
+    stride: usize,
+) {
+    for (j, (a, b)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+        // The first twiddle is one, which saves a point multiplication
+        let t = match offset + j {
+            0 => *b,
+            k => *b * twiddles[k * stride],
+        };
+        *b = *a - t;
+        *a = *a + t;
+    }
+}
+
+/// An iterative Cooley-Tukey transform, where `twiddles` holds ω^i for
+/// i < n / 2.
+fn radix2<T: TransformElement>(values: &mut [T], twiddles: &[Scalar]) {
+    let n = values.len();
+    bit_reverse_permutation(values);
+
+    let mut half = 1;
+    while half < n {
+        let stride = n / (2 * half);
+        for block in values.chunks_exact_mut(2 * half) {
+            let (lo, hi) = block.split_at_mut(half);
+            butterflies(lo, hi, twiddles, 0, stride);
+        }
+        half *= 2;
+    }
+}
+
+/// The transform of `radix2`, with each stage split across `threads`
+/// threads: by blocks in the early stages, which have many small blocks, and
+/// within blocks in the late stages.
+#[cfg(feature = "std")]
+fn radix2_parallel<T: TransformElement>(values: &mut [T], twiddles: &[Scalar], threads: usize) {
+    let n = values.len();
+    bit_reverse_permutation(values);
+
+    let mut half = 1;
+    while half < n {
+        let stride = n / (2 * half);
+        let blocks = n / (2 * half);
+        std::thread::scope(|s| {
+            if blocks >= threads {
+                let chunk_size = blocks.div_ceil(threads) * 2 * half;
+                for chunk in values.chunks_mut(chunk_size) {
+                    s.spawn(move || {
+                        for block in chunk.chunks_exact_mut(2 * half) {
+                            let (lo, hi) = block.split_at_mut(half);
+                            butterflies(lo, hi, twiddles, 0, stride);
+                        }
+                    });
+                }
+            } else {
+                let chunk_size = half.div_ceil(threads / blocks);
+                for block in values.chunks_exact_mut(2 * half) {
+                    let (lo, hi) = block.split_at_mut(half);
+                    for (k, (lo, hi)) in lo
+                        .chunks_mut(chunk_size)
+                        .zip(hi.chunks_mut(chunk_size))
+                        .enumerate()
+                    {
+                        s.spawn(move || butterflies(lo, hi, twiddles, k * chunk_size, stride));
+                    }
+                }
+            }
+        });
+        half *= 2;
+    }
+}
+
+#[cfg(test)]
+fn evaluate(coeffs: &[Scalar], x: Scalar) -> Scalar {
+    coeffs.iter().rev().fold(Scalar::ZERO, |acc, c| acc * x + c)
+}
+
+#[test]
+fn test_fft() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+
+    for log_size in 0..7 {
+        let domain = EvaluationDomain::from_log_size(log_size).unwrap();
+        let coeffs = (0..domain.size())
+            .map(|_| Scalar::random(&mut rng))
+            .collect::<Vec<_>>();
+
+        let mut values = coeffs.clone();
+        domain.fft(&mut values);
+        for (x, v) in domain.elements().zip(values.iter()) {
+            assert_eq!(evaluate(&coeffs, x), *v);
+        }
+        domain.ifft(&mut values);
+        assert_eq!(values, coeffs);
+
+        let mut values = coeffs.clone();
+        domain.coset_fft(&mut values);
+        for (x, v) in domain.elements().zip(values.iter()) {
+            assert_eq!(evaluate(&coeffs, x * domain.coset_shift()), *v);
+        }
+        domain.coset_ifft(&mut values);
+        assert_eq!(values, coeffs);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_fft_parallel() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+
+    let domain = EvaluationDomain::from_log_size(8).unwrap();
+    let coeffs = (0..domain.size())
+        .map(|_| Scalar::random(&mut rng))
+        .collect::<Vec<_>>();
+    let mut expected = coeffs.clone();
+    radix2(&mut expected, &domain.twiddles);
+
+    // More threads than blocks, and not dividing the size
+    for threads in [2, 3, 4, 7, 300] {
+        let mut values = coeffs.clone();
+        radix2_parallel(&mut values, &domain.twiddles, threads);
+        assert_eq!(values, expected);
+    }
+
+    // Group elements are spread across threads from much smaller sizes
+    #[cfg(feature = "groups")]
+    {
+        let domain = EvaluationDomain::from_log_size(6).unwrap();
+        assert!(domain.size() >= G1Projective::PARALLEL_MIN_SIZE);
+        let points = coeffs[..domain.size()]
+            .iter()
+            .map(|c| G1Projective::GENERATOR * c)
+            .collect::<Vec<_>>();
+        let mut expected = points.clone();
+        radix2(&mut expected, &domain.twiddles);
+        for threads in [2, 3] {
+            let mut values = points.clone();
+            radix2_parallel(&mut values, &domain.twiddles, threads);
+            assert_eq!(values, expected);
+        }
+    }
+}
+
+#[test]
+fn test_domain() {
+    assert!(EvaluationDomain::new(0).is_some());
+    assert_eq!(EvaluationDomain::new(1).unwrap().size(), 1);
+    assert_eq!(EvaluationDomain::new(5).unwrap().log_size(), 3);
+    assert_eq!(EvaluationDomain::new(8).unwrap().log_size(), 3);
+    assert!(EvaluationDomain::from_log_size(33).is_none());
+    #[cfg(target_pointer_width = "64")]
+    assert!(EvaluationDomain::new((1 << 32) + 1).is_none());
+
+    let domain = EvaluationDomain::new(16).unwrap();
+    assert_eq!(domain.generator() * domain.generator_inv(), Scalar::ONE);
+    assert_eq!(domain.size_inv() * Scalar::from(16u64), Scalar::ONE);
+    assert_eq!(domain.element(16), Scalar::ONE);
+    let elements = domain.elements().collect::<Vec<_>>();
+    for (i, x) in elements.iter().enumerate() {
+        assert_eq!(*x, domain.generator().pow_u64(i as u64));
+        assert_eq!(domain.evaluate_vanishing_polynomial(x), Scalar::ZERO);
+    }
+    assert_ne!(
+        domain.evaluate_vanishing_polynomial(&domain.coset_shift()),
+        Scalar::ZERO
+    );
+}
+
+#[test]
+fn test_bit_reverse_permutation() {
+    let mut values = [0, 1, 2, 3, 4, 5, 6, 7];
+    bit_reverse_permutation(&mut values);
+    assert_eq!(values, [0, 4, 2, 6, 1, 5, 3, 7]);
+    bit_reverse_permutation(&mut values);
+    assert_eq!(values, [0, 1, 2, 3, 4, 5, 6, 7]);
+
+    let mut single = [1];
+    bit_reverse_permutation(&mut single);
+    assert_eq!(single, [1]);
+}
+
+#[test]
+fn test_barycentric() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    for log_size in [0, 1, 4] {
+        let domain = EvaluationDomain::from_log_size(log_size).unwrap();
+        let coeffs = (0..domain.size())
+            .map(|_| Scalar::random(&mut rng))
+            .collect::<Vec<_>>();
+        let mut evaluations = coeffs.clone();
+        domain.fft(&mut evaluations);
+
+        let x = Scalar::random(&mut rng);
+        assert_eq!(
+            domain.evaluate_barycentric(&evaluations, &x),
+            evaluate(&coeffs, x)
+        );
+        let lagrange = domain.lagrange_coefficients(&x);
+        assert_eq!(lagrange.iter().sum::<Scalar>(), Scalar::ONE);
+
+        // Points of the domain select the corresponding evaluation
+        for (i, e) in evaluations.iter().enumerate() {
+            assert_eq!(
+                domain.evaluate_barycentric(&evaluations, &domain.element(i)),
+                *e
+            );
+        }
+    }
+}
+
+#[cfg(feature = "groups")]
+#[test]
+fn test_fft_groups() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let domain = EvaluationDomain::from_log_size(3).unwrap();
+    let coeffs = (0..8).map(|_| Scalar::random(&mut rng)).collect::<Vec<_>>();
+    let mut evaluations = coeffs.clone();
+    domain.fft(&mut evaluations);
+
+    let g1 = G1Projective::GENERATOR;
+    let points = coeffs.iter().map(|c| g1 * c).collect::<Vec<_>>();
+    let transformed = domain.fft_g1(&points);
+    for (p, e) in transformed.iter().zip(evaluations.iter()) {
+        assert_eq!(G1Projective::from(p), g1 * e);
+    }
+    let transformed = transformed
+        .iter()
+        .map(G1Projective::from)
+        .collect::<Vec<_>>();
+    let restored = domain.ifft_g1(&transformed);
+    for (p, q) in restored.iter().zip(points.iter()) {
+        assert_eq!(G1Projective::from(p), *q);
+    }
+
+    let g2 = G2Projective::GENERATOR;
+    let points = coeffs.iter().map(|c| g2 * c).collect::<Vec<_>>();
+    let transformed = domain.fft_g2(&points);
+    for (p, e) in transformed.iter().zip(evaluations.iter()) {
+        assert_eq!(G2Projective::from(p), g2 * e);
+    }
+    let transformed = transformed
+        .iter()
+        .map(G2Projective::from)
+        .collect::<Vec<_>>();
+    let restored = domain.ifft_g2(&transformed);
+    for (p, q) in restored.iter().zip(points.iter()) {
+        assert_eq!(G2Projective::from(p), *q);
+    }
+}
+
+#[cfg(feature = "groups")]
+#[test]
+fn test_lagrange_basis_setup() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let domain = EvaluationDomain::from_log_size(2).unwrap();
+    let tau = Scalar::random(&mut rng);
+    let monomial = iter::successors(Some(G1Projective::GENERATOR), |p| Some(p * tau))
+        .take(domain.size())
+        .collect::<Vec<_>>();
+    let lagrange = domain.ifft_g1(&monomial);
+    for (p, l) in lagrange.iter().zip(domain.lagrange_coefficients(&tau)) {
+        assert_eq!(G1Projective::from(p), G1Projective::GENERATOR * l);
+    }
+}
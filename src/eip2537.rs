@@ -0,0 +1,238 @@
+//! This module provides the encoding of field and group elements used by the
+//! BLS12-381 precompiles of [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537).
+//!
+//! This encoding is unrelated to the one described in
+//! [`notes::serialization`](crate::notes::serialization):
+//!
+//! * A base field element is encoded as 64 bytes in big-endian order, where the
+//!   top 16 bytes are always zero and the remaining 48 bytes are the canonical
+//!   representation of the element.
+//! * An element $c_0 + c_1 u$ of $\mathbb{F}_{p^2}$ is encoded as the encoding
+//!   of $c_0$ followed by the encoding of $c_1$.
+//! * A point is encoded as its $x$-coordinate followed by its $y$-coordinate,
+//!   and the point at infinity is encoded as all zeros. There are no flag bits.
+//! * A scalar is encoded as 32 bytes in big-endian order, and may be any 256-bit
+//!   integer rather than a canonical element of $\mathbb{F}_q$.
+//!
+//! Decoding rejects any input the precompiles would reject: nonzero padding,
+//! non-canonical field elements, and points that are not on the curve. Points
+//! are additionally checked to be in the prime order subgroup unless the
+//! `_unchecked` variant is used, which matches the behavior of the addition
+//! precompiles.
+
+use arrayref::array_ref;
+use subtle::{Choice, ConditionallySelectable, CtOption};
+
+use crate::fp::Fp;
+use crate::fp2::Fp2;
+use crate::{G1Affine, G2Affine, Scalar};
+
+mod field;
+#[cfg(all(feature = "pairings", feature = "alloc"))]
+pub mod precompile;
+
+#[cfg(feature = "expose-fields")]
+pub use field::{decode_fp, decode_fp2, encode_fp, encode_fp2};
+#[cfg(not(feature = "expose-fields"))]
+use field::{decode_fp, decode_fp2, encode_fp, encode_fp2};
+
+/// The number of bytes in an encoded base field element.
+pub const FP_BYTES: usize = 64;
+/// The number of bytes in an encoded $\mathbb{F}_{p^2}$ element.
+pub const FP2_BYTES: usize = 2 * FP_BYTES;
+/// The number of bytes in an encoded $\mathbb{G}_1$ element.
+pub const G1_BYTES: usize = 2 * FP_BYTES;
+/// The number of bytes in an encoded $\mathbb{G}_2$ element.
+pub const G2_BYTES: usize = 2 * FP2_BYTES;
+/// The number of bytes in an encoded scalar.
+pub const SCALAR_BYTES: usize = 32;
+
+/// Encodes a $\mathbb{G}_1$ element as 128 bytes.
+pub fn encode_g1(p: &G1Affine) -> [u8; G1_BYTES] {
+    // The identity is encoded as all zeros, which is also what its
+    // coordinates are.
+    let x = Fp::conditional_select(&p.x, &Fp::ZERO, p.is_identity());
+    let y = Fp::conditional_select(&p.y, &Fp::ZERO, p.is_identity());
+
+    let mut res = [0u8; G1_BYTES];
+    res[..FP_BYTES].copy_from_slice(&encode_fp(&x));
+    res[FP_BYTES..].copy_from_slice(&encode_fp(&y));
+    res
+}
+
+/// Attempts to decode a $\mathbb{G}_1$ element, failing if the encoding is
+/// invalid, the point is not on the curve, or it is not in the prime order
+/// subgroup.
+pub fn decode_g1(bytes: &[u8; G1_BYTES]) -> CtOption<G1Affine> {
+    decode_g1_unchecked(bytes).and_then(|p| CtOption::new(p, p.is_torsion_free()))
+}
+
+/// Attempts to decode a $\mathbb{G}_1$ element, failing if the encoding is
+/// invalid or the point is not on the curve. This does not check that the
+/// point is in the prime order subgroup, which the `G1ADD` precompile does
+/// not require. **Please consider using `decode_g1()` instead.**
+pub fn decode_g1_unchecked(bytes: &[u8; G1_BYTES]) -> CtOption<G1Affine> {
+    let x = decode_fp(array_ref![bytes, 0, FP_BYTES]);
+    let y = decode_fp(array_ref![bytes, FP_BYTES, FP_BYTES]);
+
+    x.and_then(|x| {
+        y.and_then(|y| {
+            let infinity = x.is_zero() & y.is_zero();
+            let p = G1Affine::conditional_select(
+                &G1Affine {
+                    x,
+                    y,
+                    infinity: Choice::from(0u8),
+                },
+                &G1Affine::identity(),
+                infinity,
+            );
+
+            CtOption::new(p, p.is_on_curve())
+        })
+    })
+}
+
+/// Encodes a $\mathbb{G}_2$ element as 256 bytes.
+pub fn encode_g2(p: &G2Affine) -> [u8; G2_BYTES] {
+    // The identity is encoded as all zeros, which is also what its
+    // coordinates are.
+    let x = Fp2::conditional_select(&p.x, &Fp2::ZERO, p.is_identity());
+    let y = Fp2::conditional_select(&p.y, &Fp2::ZERO, p.is_identity());
+
+    let mut res = [0u8; G2_BYTES];
+    res[..FP2_BYTES].copy_from_slice(&encode_fp2(&x));
+    res[FP2_BYTES..].copy_from_slice(&encode_fp2(&y));
+    res
+}
+
+/// Attempts to decode a $\mathbb{G}_2$ element, failing if the encoding is
+/// invalid, the point is not on the curve, or it is not in the prime order
+/// subgroup.
+pub fn decode_g2(bytes: &[u8; G2_BYTES]) -> CtOption<G2Affine> {
+    decode_g2_unchecked(bytes).and_then(|p| CtOption::new(p, p.is_torsion_free()))
+}
+
+/// Attempts to decode a $\mathbb{G}_2$ element, failing if the encoding is
+/// invalid or the point is not on the curve. This does not check that the
+/// point is in the prime order subgroup, which the `G2ADD` precompile does
+/// not require. **Please consider using `decode_g2()` instead.**
+pub fn decode_g2_unchecked(bytes: &[u8; G2_BYTES]) -> CtOption<G2Affine> {
+    let x = decode_fp2(array_ref![bytes, 0, FP2_BYTES]);
+    let y = decode_fp2(array_ref![bytes, FP2_BYTES, FP2_BYTES]);
+
+    x.and_then(|x| {
+        y.and_then(|y| {
+            let infinity = x.is_zero() & y.is_zero();
+            let p = G2Affine::conditional_select(
+                &G2Affine {
+                    x,
+                    y,
+                    infinity: Choice::from(0u8),
+                },
+                &G2Affine::identity(),
+                infinity,
+            );
+
+            CtOption::new(p, p.is_on_curve())
+        })
+    })
+}
+
+/// Encodes a scalar as 32 big-endian bytes.
+pub fn encode_scalar(s: &Scalar) -> [u8; SCALAR_BYTES] {
+    s.to_be_bytes()
+}
+
+/// Decodes a scalar from 32 big-endian bytes. Every input is accepted, and
+/// integers that are not less than the group order are reduced.
+pub fn decode_scalar(bytes: &[u8; SCALAR_BYTES]) -> Scalar {
+    let mut wide = [0u8; 64];
+    for (w, b) in wide.iter_mut().zip(bytes.iter().rev()) {
+        *w = *b;
+    }
+    Scalar::from_bytes_wide(&wide)
+}
+
+#[test]
+fn test_g1_encoding() {
+    let g = G1Affine::generator();
+    let bytes = encode_g1(&g);
+    assert_eq!(&bytes[16..64], &g.to_uncompressed()[..48]);
+    assert_eq!(&bytes[80..], &g.to_uncompressed()[48..]);
+    assert_eq!(decode_g1(&bytes).unwrap(), g);
+
+    let id = encode_g1(&G1Affine::identity());
+    assert_eq!(&id[..], &[0u8; G1_BYTES][..]);
+    assert!(bool::from(decode_g1(&id).unwrap().is_identity()));
+
+    // Not on the curve
+    let mut bad = bytes;
+    bad[127] ^= 1;
+    assert!(bool::from(decode_g1_unchecked(&bad).is_none()));
+
+    // (0, 1) is not on the curve, and (0, 2) is on the curve but not in the
+    // subgroup
+    let mut bad = [0u8; G1_BYTES];
+    bad[127] = 1;
+    assert!(bool::from(decode_g1_unchecked(&bad).is_none()));
+    bad[127] = 2;
+    assert!(bool::from(decode_g1_unchecked(&bad).is_some()));
+    assert!(bool::from(decode_g1(&bad).is_none()));
+
+    // On the curve but not in the subgroup
+    let a = G1Affine {
+        x: Fp::from_raw_unchecked([
+            0x0aba_f895_b97e_43c8,
+            0xba4c_6432_eb9b_61b0,
+            0x1250_6f52_adfe_307f,
+            0x7502_8c34_3933_6b72,
+            0x8474_4f05_b8e9_bd71,
+            0x113d_554f_b095_54f7,
+        ]),
+        y: Fp::from_raw_unchecked([
+            0x73e9_0e88_f5cf_01c0,
+            0x3700_7b65_dd31_97e2,
+            0x5cf9_a199_2f0d_7c78,
+            0x4f83_c10b_9eb3_330d,
+            0xf6a6_3f6f_07f6_0961,
+            0x0c53_b5b9_7e63_4df3,
+        ]),
+        infinity: Choice::from(0u8),
+    };
+    let bytes = encode_g1(&a);
+    assert_eq!(decode_g1_unchecked(&bytes).unwrap(), a);
+    assert!(bool::from(decode_g1(&bytes).is_none()));
+}
+
+#[test]
+fn test_g2_encoding() {
+    let g = G2Affine::generator();
+    let bytes = encode_g2(&g);
+    assert_eq!(decode_g2(&bytes).unwrap(), g);
+    assert_eq!(decode_fp2(array_ref![bytes, 0, FP2_BYTES]).unwrap(), g.x);
+
+    let id = encode_g2(&G2Affine::identity());
+    assert_eq!(&id[..], &[0u8; G2_BYTES][..]);
+    assert!(bool::from(decode_g2(&id).unwrap().is_identity()));
+
+    let mut bad = bytes;
+    bad[255] ^= 1;
+    assert!(bool::from(decode_g2_unchecked(&bad).is_none()));
+
+    let mut bad = bytes;
+    bad[FP2_BYTES] = 1;
+    assert!(bool::from(decode_g2_unchecked(&bad).is_none()));
+}
+
+#[test]
+fn test_scalar_encoding() {
+    let s = Scalar::from(0x1234_5678u64);
+    let bytes = encode_scalar(&s);
+    assert_eq!(decode_scalar(&bytes), s);
+
+    // Scalars are reduced rather than rejected
+    let mut bytes = (-Scalar::ONE).to_be_bytes();
+    bytes[31] += 2;
+    assert_eq!(decode_scalar(&bytes), Scalar::ONE);
+}
@@ -0,0 +1,86 @@
+//! Encoding of base field and $\mathbb{F}_{p^2}$ elements, which are the
+//! inputs of the map-to-curve precompiles. These are only public with the
+//! `expose-fields` feature.
+
+use arrayref::array_ref;
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+use super::{FP2_BYTES, FP_BYTES};
+use crate::fp::Fp;
+use crate::fp2::Fp2;
+
+/// The number of leading zero bytes in an encoded base field element.
+const FP_PADDING: usize = FP_BYTES - 48;
+
+/// Encodes a base field element as 64 big-endian bytes.
+pub fn encode_fp(e: &Fp) -> [u8; FP_BYTES] {
+    let mut res = [0u8; FP_BYTES];
+    res[FP_PADDING..].copy_from_slice(&e.to_bytes());
+    res
+}
+
+/// Attempts to decode a base field element, failing if the padding is nonzero
+/// or the element is not canonical.
+pub fn decode_fp(bytes: &[u8; FP_BYTES]) -> CtOption<Fp> {
+    let padding_is_zero = bytes[..FP_PADDING]
+        .iter()
+        .fold(Choice::from(1u8), |acc, b| acc & b.ct_eq(&0));
+
+    let mut tmp = [0u8; 48];
+    tmp.copy_from_slice(&bytes[FP_PADDING..]);
+
+    Fp::from_bytes(&tmp).and_then(|e| CtOption::new(e, padding_is_zero))
+}
+
+/// Encodes an $\mathbb{F}_{p^2}$ element as 128 big-endian bytes.
+pub fn encode_fp2(e: &Fp2) -> [u8; FP2_BYTES] {
+    let mut res = [0u8; FP2_BYTES];
+    res[..FP_BYTES].copy_from_slice(&encode_fp(&e.c0));
+    res[FP_BYTES..].copy_from_slice(&encode_fp(&e.c1));
+    res
+}
+
+/// Attempts to decode an $\mathbb{F}_{p^2}$ element, failing if either
+/// coefficient fails to decode.
+pub fn decode_fp2(bytes: &[u8; FP2_BYTES]) -> CtOption<Fp2> {
+    let c0 = decode_fp(array_ref![bytes, 0, FP_BYTES]);
+    let c1 = decode_fp(array_ref![bytes, FP_BYTES, FP_BYTES]);
+
+    c0.and_then(|c0| c1.map(|c1| Fp2 { c0, c1 }))
+}
+
+#[test]
+fn test_fp_encoding() {
+    let a = Fp::ONE + Fp::ONE;
+    let bytes = encode_fp(&a);
+    assert_eq!(&bytes[..63], &[0u8; 63][..]);
+    assert_eq!(bytes[63], 2);
+    assert_eq!(decode_fp(&bytes).unwrap(), a);
+
+    // Nonzero padding
+    let mut bad = bytes;
+    bad[15] = 1;
+    assert!(bool::from(decode_fp(&bad).is_none()));
+
+    // The modulus is not canonical
+    let mut bad = [0u8; FP_BYTES];
+    bad[FP_PADDING..].copy_from_slice(&(-Fp::ONE).to_bytes());
+    bad[63] += 1;
+    assert!(bool::from(decode_fp(&bad).is_none()));
+}
+
+#[test]
+fn test_fp2_encoding() {
+    let a = Fp2 {
+        c0: Fp::ONE,
+        c1: Fp::ONE + Fp::ONE,
+    };
+    let bytes = encode_fp2(&a);
+    assert_eq!(bytes[63], 1);
+    assert_eq!(bytes[127], 2);
+    assert_eq!(decode_fp2(&bytes).unwrap(), a);
+
+    let mut bad = bytes;
+    bad[FP_BYTES] = 1;
+    assert!(bool::from(decode_fp2(&bad).is_none()));
+}
@@ -0,0 +1,736 @@
+//! This module provides the KZG commitments to blobs of
+//! [EIP-4844](https://eips.ethereum.org/EIPS/eip-4844), following the
+//! `polynomial-commitments` functions of the Deneb consensus specifications.
+//!
+//! A blob holds the evaluations of a polynomial of degree less than 4096 over
+//! the domain of size 4096, as 32-byte big-endian canonical field elements in
+//! bit-reversed order. Commitments and proofs are compressed $\mathbb{G}_1$
+//! points in the encoding described in
+//! [`notes::serialization`](crate::notes::serialization).
+//!
+//! Inputs the specifications reject with an assertion are reported as an
+//! [`Eip4844Error`], while proofs that are well formed but wrong verify as
+//! `false`.
+//!
+//! The functions take a [`TrustedSetup`], which is usually loaded from the
+//! text file distributed with client implementations using
+//! [`TrustedSetup::from_text`].
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use arrayref::array_ref;
+use sha2::{Digest, Sha256};
+
+use crate::domain::bit_reverse_permutation;
+use crate::kzg::{Basis, Srs};
+use crate::{G1Affine, G2Affine, Scalar};
+
+/// The number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// The number of bytes in an encoded field element.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// The number of bytes in a blob.
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+/// The number of bytes in an encoded commitment.
+pub const BYTES_PER_COMMITMENT: usize = 48;
+/// The number of bytes in an encoded proof.
+pub const BYTES_PER_PROOF: usize = 48;
+
+/// The domain separator of the challenge of a blob proof.
+const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8; 16] = b"FSBLOBVERIFY_V1_";
+/// The domain separator of the weights of a batch of proofs.
+const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &[u8; 16] = b"RCKZGBATCH___V1_";
+
+/// A blob of field elements.
+pub type Blob = [u8; BYTES_PER_BLOB];
+/// An encoded commitment to a blob.
+pub type KzgCommitment = [u8; BYTES_PER_COMMITMENT];
+/// An encoded proof of the value of a blob at a point.
+pub type KzgProof = [u8; BYTES_PER_PROOF];
+
+/// The reasons an input can be rejected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Eip4844Error {
+    /// A field element of a blob, or an evaluation point or value, is not
+    /// less than the modulus.
+    InvalidFieldElement,
+    /// A commitment or proof is not the compressed encoding of a point in the
+    /// prime order subgroup.
+    InvalidPoint,
+    /// The numbers of blobs, commitments and proofs differ.
+    LengthMismatch,
+    /// The trusted setup has the wrong number of points or an invalid line.
+    InvalidTrustedSetup,
+}
+
+impl Display for Eip4844Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFieldElement => write!(f, "non-canonical field element"),
+            Self::InvalidPoint => write!(f, "invalid commitment or proof"),
+            Self::LengthMismatch => {
+                write!(f, "the numbers of blobs, commitments and proofs differ")
+            }
+            Self::InvalidTrustedSetup => write!(f, "invalid trusted setup"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Eip4844Error {}
+
+/// The trusted setup of EIP-4844: the Lagrange basis of the domain of size
+/// 4096 in $\mathbb{G}_1$ and the first powers of τ in $\mathbb{G}_2$.
+#[derive(Clone, Debug)]
+pub struct TrustedSetup {
+    srs: Srs,
+}
+
+impl TrustedSetup {
+    /// Creates a trusted setup from the points `L_i(τ) G1` of the Lagrange
+    /// basis in natural order and the points `τ^i G2`, failing unless there
+    /// are 4096 points of $\mathbb{G}_1$ and at least two of $\mathbb{G}_2$.
+    pub fn new(g1_lagrange: &[G1Affine], g2_monomial: &[G2Affine]) -> Result<Self, Eip4844Error> {
+        if g1_lagrange.len() != FIELD_ELEMENTS_PER_BLOB || g2_monomial.len() < 2 {
+            return Err(Eip4844Error::InvalidTrustedSetup);
+        }
+        Ok(Self {
+            srs: Srs::new(Basis::Lagrange, g1_lagrange, g2_monomial),
+        })
+    }
+
+    /// Parses a trusted setup in the text format used by client
+    /// implementations: the number of $\mathbb{G}_1$ points and the number of
+    /// $\mathbb{G}_2$ points on one line each, followed by the hex encoded
+    /// compressed points `L_i(τ) G1` and `τ^i G2`, one per line. Every point
+    /// is checked to be in the prime order subgroup.
+    ///
+    /// Newer files also end with the points `τ^i G1` of the monomial basis,
+    /// which are not needed for blobs and are skipped without being decoded.
+    pub fn from_text(text: &str) -> Result<Self, Eip4844Error> {
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let count = |i: usize| lines.get(i).and_then(|line| line.parse::<usize>().ok());
+        let (Some(g1_len), Some(g2_len)) = (count(0), count(1)) else {
+            return Err(Eip4844Error::InvalidTrustedSetup);
+        };
+
+        // Check the number of lines before decoding any points, without
+        // trusting the counts not to overflow
+        if g1_len != FIELD_ELEMENTS_PER_BLOB || g2_len < 2 {
+            return Err(Eip4844Error::InvalidTrustedSetup);
+        }
+        let points = &lines[2..];
+        let monomial_len = g1_len
+            .checked_add(g2_len)
+            .and_then(|len| points.len().checked_sub(len));
+        if !matches!(monomial_len, Some(0 | FIELD_ELEMENTS_PER_BLOB)) {
+            return Err(Eip4844Error::InvalidTrustedSetup);
+        }
+
+        let g1 = points[..g1_len]
+            .iter()
+            .map(|line| {
+                decode_hex(line)
+                    .and_then(|bytes| G1Affine::from_compressed(&bytes).into())
+                    .ok_or(Eip4844Error::InvalidTrustedSetup)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let g2 = points[g1_len..g1_len + g2_len]
+            .iter()
+            .map(|line| {
+                decode_hex(line)
+                    .and_then(|bytes| G2Affine::from_compressed(&bytes).into())
+                    .ok_or(Eip4844Error::InvalidTrustedSetup)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(&g1, &g2)
+    }
+
+    /// Returns the setup as a reference string for the [`kzg`](crate::kzg)
+    /// functions, whose Lagrange basis is in natural rather than bit-reversed
+    /// order.
+    pub fn srs(&self) -> &Srs {
+        &self.srs
+    }
+}
+
+/// Computes the commitment to a blob.
+pub fn blob_to_kzg_commitment(
+    blob: &Blob,
+    setup: &TrustedSetup,
+) -> Result<KzgCommitment, Eip4844Error> {
+    let evaluations = blob_to_evaluations(blob)?;
+    Ok(setup.srs.commit_evaluations(&evaluations).to_compressed())
+}
+
+/// Computes a proof of the value of the polynomial of a blob at `z`,
+/// returning the proof and the value.
+pub fn compute_kzg_proof(
+    blob: &Blob,
+    z: &[u8; BYTES_PER_FIELD_ELEMENT],
+    setup: &TrustedSetup,
+) -> Result<(KzgProof, [u8; BYTES_PER_FIELD_ELEMENT]), Eip4844Error> {
+    let evaluations = blob_to_evaluations(blob)?;
+    let z = bytes_to_bls_field(z)?;
+    let (proof, value) = setup.srs.open_evaluations(&evaluations, &z);
+    Ok((proof.to_compressed(), value.to_be_bytes()))
+}
+
+/// Computes the proof of the value of the polynomial of a blob at the
+/// challenge derived from the blob and its commitment.
+pub fn compute_blob_kzg_proof(
+    blob: &Blob,
+    commitment: &KzgCommitment,
+    setup: &TrustedSetup,
+) -> Result<KzgProof, Eip4844Error> {
+    bytes_to_kzg_point(commitment)?;
+    let evaluations = blob_to_evaluations(blob)?;
+    let challenge = compute_challenge(blob, commitment);
+    let (proof, _) = setup.srs.open_evaluations(&evaluations, &challenge);
+    Ok(proof.to_compressed())
+}
+
+/// Checks a proof that the polynomial committed to by `commitment` has the
+/// value `y` at `z`.
+pub fn verify_kzg_proof(
+    commitment: &KzgCommitment,
+    z: &[u8; BYTES_PER_FIELD_ELEMENT],
+    y: &[u8; BYTES_PER_FIELD_ELEMENT],
+    proof: &KzgProof,
+    setup: &TrustedSetup,
+) -> Result<bool, Eip4844Error> {
+    let commitment = bytes_to_kzg_point(commitment)?;
+    let z = bytes_to_bls_field(z)?;
+    let y = bytes_to_bls_field(y)?;
+    let proof = bytes_to_kzg_point(proof)?;
+    Ok(setup.srs.verify(&commitment, &z, &y, &proof))
+}
+
+/// Checks a proof computed by [`compute_blob_kzg_proof`] for a blob and its
+/// commitment.
+pub fn verify_blob_kzg_proof(
+    blob: &Blob,
+    commitment: &KzgCommitment,
+    proof: &KzgProof,
+    setup: &TrustedSetup,
+) -> Result<bool, Eip4844Error> {
+    let (point, challenge, value) = blob_opening(blob, commitment, setup)?;
+    let proof = bytes_to_kzg_point(proof)?;
+    Ok(setup.srs.verify(&point, &challenge, &value, &proof))
+}
+
+/// Checks many proofs computed by [`compute_blob_kzg_proof`] at once, with
+/// a single pairing check on a linear combination of them whose weights are
+/// derived from every input.
+pub fn verify_blob_kzg_proof_batch(
+    blobs: &[Blob],
+    commitments: &[KzgCommitment],
+    proofs: &[KzgProof],
+    setup: &TrustedSetup,
+) -> Result<bool, Eip4844Error> {
+    let n = blobs.len();
+    if commitments.len() != n || proofs.len() != n {
+        return Err(Eip4844Error::LengthMismatch);
+    }
+
+    let mut points = Vec::with_capacity(n);
+    let mut challenges = Vec::with_capacity(n);
+    let mut values = Vec::with_capacity(n);
+    let mut proof_points = Vec::with_capacity(n);
+    for ((blob, commitment), proof) in blobs.iter().zip(commitments).zip(proofs) {
+        let (point, challenge, value) = blob_opening(blob, commitment, setup)?;
+        points.push(point);
+        challenges.push(challenge);
+        values.push(value);
+        proof_points.push(bytes_to_kzg_point(proof)?);
+    }
+
+    // The weights are the powers of a challenge derived from every input
+    let mut hasher = Sha256::new();
+    hasher.update(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN);
+    hasher.update((FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    hasher.update((n as u64).to_be_bytes());
+    for i in 0..n {
+        hasher.update(commitments[i]);
+        hasher.update(challenges[i].to_be_bytes());
+        hasher.update(values[i].to_be_bytes());
+        hasher.update(proofs[i]);
+    }
+    let r = Scalar::from_bytes_mod_order(&hasher.finalize());
+    let weights = core::iter::successors(Some(Scalar::ONE), |w| Some(w * r))
+        .take(n)
+        .collect::<Vec<_>>();
+
+    Ok(setup
+        .srs
+        .verify_batch_with_weights(&points, &challenges, &values, &proof_points, &weights))
+}
+
+/// Decodes a commitment and computes the challenge of a blob and the value
+/// of its polynomial there.
+fn blob_opening(
+    blob: &Blob,
+    commitment: &KzgCommitment,
+    setup: &TrustedSetup,
+) -> Result<(G1Affine, Scalar, Scalar), Eip4844Error> {
+    let point = bytes_to_kzg_point(commitment)?;
+    let evaluations = blob_to_evaluations(blob)?;
+    let challenge = compute_challenge(blob, commitment);
+    let domain = setup.srs.domain().expect("the setup has a Lagrange basis");
+    let value = domain.evaluate_barycentric(&evaluations, &challenge);
+    Ok((point, challenge, value))
+}
+
+/// Derives the evaluation point of a blob proof from the blob and its
+/// commitment.
+fn compute_challenge(blob: &Blob, commitment: &KzgCommitment) -> Scalar {
+    let mut hasher = Sha256::new();
+    hasher.update(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    hasher.update((FIELD_ELEMENTS_PER_BLOB as u128).to_be_bytes());
+    hasher.update(blob);
+    hasher.update(commitment);
+    Scalar::from_bytes_mod_order(&hasher.finalize())
+}
+
+/// Decodes the field elements of a blob and returns them in natural order.
+fn blob_to_evaluations(blob: &Blob) -> Result<Vec<Scalar>, Eip4844Error> {
+    let mut evaluations = blob
+        .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .map(|bytes| bytes_to_bls_field(array_ref![bytes, 0, BYTES_PER_FIELD_ELEMENT]))
+        .collect::<Result<Vec<_>, _>>()?;
+    bit_reverse_permutation(&mut evaluations);
+    Ok(evaluations)
+}
+
+fn bytes_to_bls_field(bytes: &[u8; BYTES_PER_FIELD_ELEMENT]) -> Result<Scalar, Eip4844Error> {
+    Option::from(Scalar::from_be_bytes(bytes)).ok_or(Eip4844Error::InvalidFieldElement)
+}
+
+/// Decodes a commitment or proof, which may be the identity.
+fn bytes_to_kzg_point(bytes: &[u8; BYTES_PER_COMMITMENT]) -> Result<G1Affine, Eip4844Error> {
+    Option::from(G1Affine::from_compressed(bytes)).ok_or(Eip4844Error::InvalidPoint)
+}
+
+/// Decodes a hex string with an optional `0x` prefix into an array.
+fn decode_hex<const N: usize>(line: &str) -> Option<[u8; N]> {
+    let mut bytes = [0u8; N];
+    hex::decode_to_slice(line.strip_prefix("0x").unwrap_or(line), &mut bytes).ok()?;
+    Some(bytes)
+}
+
+/// A setup from a known τ, in the text format, shared between tests as it is
+/// slow to compute without optimizations.
+#[cfg(test)]
+fn test_setup() -> &'static (std::string::String, TrustedSetup) {
+    use crate::{EvaluationDomain, G1Projective, G2Projective};
+    use std::fmt::Write;
+
+    static SETUP: std::sync::OnceLock<(std::string::String, TrustedSetup)> =
+        std::sync::OnceLock::new();
+    SETUP.get_or_init(|| {
+        let tau = test_tau();
+        let domain = EvaluationDomain::from_log_size(12).unwrap();
+
+        // A fixed base table of j 256^k G1, which replaces the doublings of
+        // each multiplication with 32 additions
+        let mut table = Vec::with_capacity(32);
+        let mut base = G1Projective::GENERATOR;
+        for _ in 0..32 {
+            let row = core::iter::successors(Some(G1Projective::IDENTITY), |p| Some(p + base))
+                .take(256)
+                .collect::<Vec<_>>();
+            base = row[255] + base;
+            table.push(row);
+        }
+        let g1 = domain
+            .lagrange_coefficients(&tau)
+            .iter()
+            .map(|l| {
+                let bytes = l.to_le_bytes();
+                G1Affine::from(
+                    table
+                        .iter()
+                        .zip(bytes)
+                        .fold(G1Projective::IDENTITY, |acc, (row, b)| {
+                            acc + row[b as usize]
+                        }),
+                )
+            })
+            .collect::<Vec<_>>();
+        let g2 = [
+            G2Affine::generator(),
+            G2Affine::from(G2Projective::GENERATOR * tau),
+        ];
+
+        let mut text = std::format!("{}\n{}\n", g1.len(), g2.len());
+        for p in &g1 {
+            writeln!(text, "{}", hex::encode(p.to_compressed())).unwrap();
+        }
+        for p in &g2 {
+            writeln!(text, "0x{}", hex::encode(p.to_compressed())).unwrap();
+        }
+        // The trailing monomial basis is not decoded, so it is not computed
+        for _ in 0..FIELD_ELEMENTS_PER_BLOB {
+            writeln!(
+                text,
+                "{}",
+                hex::encode(G1Affine::generator().to_compressed())
+            )
+            .unwrap();
+        }
+        let setup = TrustedSetup::from_text(&text).unwrap();
+        (text, setup)
+    })
+}
+
+#[cfg(test)]
+fn test_tau() -> Scalar {
+    Scalar::from(0x1234_5678_9abc_def0u64)
+}
+
+/// A blob with the evaluations of a random polynomial, which is returned.
+#[cfg(test)]
+fn test_blob(seed: u8) -> (std::boxed::Box<Blob>, crate::Polynomial) {
+    use crate::{EvaluationDomain, Polynomial};
+    use rand_core::SeedableRng;
+
+    let rng = rand_xorshift::XorShiftRng::from_seed([seed; 16]);
+    let p = Polynomial::random(FIELD_ELEMENTS_PER_BLOB - 1, rng);
+    let domain = EvaluationDomain::from_log_size(12).unwrap();
+    let mut evaluations = p.evaluate_over_domain(&domain);
+    bit_reverse_permutation(&mut evaluations);
+
+    let mut blob = std::boxed::Box::new([0u8; BYTES_PER_BLOB]);
+    for (bytes, e) in blob
+        .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT)
+        .zip(evaluations)
+    {
+        bytes.copy_from_slice(&e.to_be_bytes());
+    }
+    (blob, p)
+}
+
+#[test]
+fn test_blob_proofs() {
+    use crate::{EvaluationDomain, G1Projective};
+
+    let (_, setup) = test_setup();
+    let (blob, p) = test_blob(1);
+
+    // The commitment is p(τ) G1, so the blob is in bit-reversed order
+    let commitment = blob_to_kzg_commitment(&blob, setup).unwrap();
+    assert_eq!(
+        commitment,
+        G1Affine::from(G1Projective::GENERATOR * p.evaluate(&test_tau())).to_compressed()
+    );
+
+    let blob_proof = compute_blob_kzg_proof(&blob, &commitment, setup).unwrap();
+    assert_eq!(
+        verify_blob_kzg_proof(&blob, &commitment, &blob_proof, setup),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_blob_kzg_proof(
+            &blob,
+            &G1Affine::generator().to_compressed(),
+            &blob_proof,
+            setup
+        ),
+        Ok(false)
+    );
+
+    // The challenge agrees with the single point functions
+    let z = compute_challenge(&blob, &commitment);
+    let y = p.evaluate(&z);
+    assert_eq!(
+        verify_kzg_proof(
+            &commitment,
+            &z.to_be_bytes(),
+            &y.to_be_bytes(),
+            &blob_proof,
+            setup
+        ),
+        Ok(true)
+    );
+
+    // A point of the domain, where the value is an element of the blob
+    let z = EvaluationDomain::from_log_size(12).unwrap().element(5);
+    let (proof, y) = compute_kzg_proof(&blob, &z.to_be_bytes(), setup).unwrap();
+    assert_eq!(y, p.evaluate(&z).to_be_bytes());
+    assert_eq!(
+        y,
+        *array_ref![
+            blob,
+            5 * 512 * BYTES_PER_FIELD_ELEMENT,
+            BYTES_PER_FIELD_ELEMENT
+        ]
+    );
+    assert_eq!(
+        verify_kzg_proof(&commitment, &z.to_be_bytes(), &y, &proof, setup),
+        Ok(true)
+    );
+    let wrong = (Scalar::from_be_bytes(&y).unwrap() + Scalar::ONE).to_be_bytes();
+    assert_eq!(
+        verify_kzg_proof(&commitment, &z.to_be_bytes(), &wrong, &proof, setup),
+        Ok(false)
+    );
+
+    // A batch with a second blob
+    let (other, _) = test_blob(2);
+    let other_commitment = blob_to_kzg_commitment(&other, setup).unwrap();
+    let other_proof = compute_blob_kzg_proof(&other, &other_commitment, setup).unwrap();
+    let blobs = [*blob, *other];
+    let commitments = [commitment, other_commitment];
+    assert_eq!(
+        verify_blob_kzg_proof_batch(&blobs, &commitments, &[blob_proof, other_proof], setup),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_blob_kzg_proof_batch(&blobs, &commitments, &[other_proof, blob_proof], setup),
+        Ok(false)
+    );
+    assert_eq!(
+        verify_blob_kzg_proof_batch(&blobs, &commitments[..1], &[blob_proof], setup),
+        Err(Eip4844Error::LengthMismatch)
+    );
+    assert_eq!(verify_blob_kzg_proof_batch(&[], &[], &[], setup), Ok(true));
+}
+
+#[test]
+fn test_invalid_inputs() {
+    let (_, setup) = test_setup();
+    let infinity = G1Affine::identity().to_compressed();
+    let zero = [0u8; BYTES_PER_FIELD_ELEMENT];
+
+    // The zero polynomial commits to the identity, which is a valid point
+    let mut blob = std::boxed::Box::new([0u8; BYTES_PER_BLOB]);
+    assert_eq!(blob_to_kzg_commitment(&blob, setup), Ok(infinity));
+    assert_eq!(
+        verify_kzg_proof(&infinity, &zero, &zero, &infinity, setup),
+        Ok(true)
+    );
+
+    // The modulus is not canonical
+    let modulus = (-Scalar::ONE).to_be_bytes();
+    let mut modulus = modulus;
+    modulus[31] += 1;
+    blob[..BYTES_PER_FIELD_ELEMENT].copy_from_slice(&modulus);
+    assert_eq!(
+        blob_to_kzg_commitment(&blob, setup),
+        Err(Eip4844Error::InvalidFieldElement)
+    );
+    assert_eq!(
+        verify_kzg_proof(&infinity, &modulus, &zero, &infinity, setup),
+        Err(Eip4844Error::InvalidFieldElement)
+    );
+
+    // Points must be compressed, on the curve and in the subgroup
+    let mut invalid = infinity;
+    invalid[0] = 0x40;
+    assert_eq!(
+        verify_kzg_proof(&invalid, &zero, &zero, &infinity, setup),
+        Err(Eip4844Error::InvalidPoint)
+    );
+    let not_on_curve = [0x80u8; BYTES_PER_COMMITMENT];
+    assert_eq!(
+        compute_blob_kzg_proof(&blob, &not_on_curve, setup),
+        Err(Eip4844Error::InvalidPoint)
+    );
+}
+
+#[test]
+fn test_trusted_setup_text() {
+    let (text, setup) = test_setup();
+    assert_eq!(
+        setup.srs().domain().map(|d| d.size()),
+        Some(FIELD_ELEMENTS_PER_BLOB)
+    );
+
+    // The shared setup is in the newer format, and the older one omits the
+    // monomial basis
+    let lines = text.lines().collect::<Vec<_>>();
+    let truncated = lines[..lines.len() - 1].join("\n");
+    let first_point = lines[2];
+    let invalid = [
+        std::string::String::new(),
+        text.replacen("4096", "4095", 1),
+        text.replacen("\n2\n", "\n1\n", 1),
+        text.replacen("\n2\n", "\n3\n", 1),
+        std::format!("{}extra\n", text),
+        truncated,
+        text.replacen(first_point, &"zz".repeat(48), 1),
+        text.replacen(first_point, &hex::encode([0x80u8; 48]), 1),
+        // The counts overflow to the number of lines
+        std::format!(
+            "4096\n{}\n{}",
+            usize::MAX - 4000,
+            lines[2..2 + 4096 + 95].join("\n")
+        ),
+    ];
+    for text in &invalid {
+        assert_eq!(
+            TrustedSetup::from_text(text).unwrap_err(),
+            Eip4844Error::InvalidTrustedSetup
+        );
+    }
+    assert!(TrustedSetup::new(&[G1Affine::generator()], &[G2Affine::generator(); 2]).is_err());
+}
+
+/// The fields of a `data.yaml` file of the consensus specification tests, as
+/// the tokens following each key. This is just enough YAML for the KZG tests,
+/// whose values are `0x` hex strings, lists of them, `null` and booleans.
+#[cfg(test)]
+fn parse_test_data(yaml: &str) -> std::collections::HashMap<&str, Vec<&str>> {
+    let mut fields = std::collections::HashMap::new();
+    let mut key = "";
+    for line in yaml.lines() {
+        let mut rest = line.trim();
+        if let Some((k, v)) = rest.split_once(':') {
+            if k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                key = k;
+                rest = v;
+            }
+        }
+        fields.entry(key).or_insert_with(Vec::new).extend(
+            rest.split(|c: char| c.is_whitespace() || "[],-'\"".contains(c))
+                .filter(|token| !token.is_empty()),
+        );
+    }
+    fields
+}
+
+/// Decodes the hex strings under a key of a test into arrays, failing if any
+/// has the wrong length.
+#[cfg(test)]
+fn decode_test_data<const N: usize>(
+    data: &std::collections::HashMap<&str, Vec<&str>>,
+    key: &str,
+) -> Option<Vec<[u8; N]>> {
+    data.get(key)
+        .map_or(&[][..], Vec::as_slice)
+        .iter()
+        .map(|token| decode_hex(token))
+        .collect()
+}
+
+#[test]
+#[ignore = "needs KZG_TRUSTED_SETUP and KZG_VECTORS to point at the official files"]
+fn test_official_vectors() {
+    use crate::{EvaluationDomain, G1Projective};
+
+    // The official trusted setup and test vectors are not vendored. Point
+    // KZG_TRUSTED_SETUP at trusted_setup.txt and KZG_VECTORS at the
+    // tests/general/deneb/kzg directory of the consensus specification tests
+    // and run the ignored tests to check them.
+    let path = std::env::var("KZG_TRUSTED_SETUP").expect("KZG_TRUSTED_SETUP is not set");
+    let text = std::fs::read_to_string(&path).unwrap();
+    let setup = TrustedSetup::from_text(&text).unwrap();
+
+    // The points of the Lagrange basis are in natural order, which the
+    // trailing monomial basis of newer files shows
+    let lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    let g2_len = lines[1].parse::<usize>().unwrap();
+    let lagrange = &lines[2..2 + FIELD_ELEMENTS_PER_BLOB];
+    let monomial = &lines[2 + FIELD_ELEMENTS_PER_BLOB + g2_len..];
+    if !monomial.is_empty() {
+        let monomial = monomial
+            .iter()
+            .map(|line| {
+                G1Projective::from(G1Affine::from_compressed(&decode_hex(line).unwrap()).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let domain = EvaluationDomain::from_log_size(12).unwrap();
+        for (p, line) in domain.ifft_g1(&monomial).iter().zip(lagrange) {
+            assert_eq!(
+                Some(p.to_compressed()),
+                decode_hex(line),
+                "the Lagrange basis is not in natural order"
+            );
+        }
+    }
+
+    let dir = std::env::var("KZG_VECTORS").expect("KZG_VECTORS is not set");
+    for function in [
+        "blob_to_kzg_commitment",
+        "compute_kzg_proof",
+        "compute_blob_kzg_proof",
+        "verify_kzg_proof",
+        "verify_blob_kzg_proof",
+        "verify_blob_kzg_proof_batch",
+    ] {
+        let cases = std::path::Path::new(&dir)
+            .join(function)
+            .join("kzg-mainnet");
+        let cases = std::fs::read_dir(&cases)
+            .unwrap_or_else(|_| panic!("no test vectors found in {}", cases.display()));
+        for case in cases {
+            let case = case.unwrap().path();
+            let yaml = std::fs::read_to_string(case.join("data.yaml")).unwrap();
+            let data = parse_test_data(&yaml);
+            let blob = decode_test_data::<BYTES_PER_BLOB>(&data, "blob");
+            let commitment = decode_test_data::<BYTES_PER_COMMITMENT>(&data, "commitment");
+            let proof = decode_test_data::<BYTES_PER_PROOF>(&data, "proof");
+            let z = decode_test_data::<BYTES_PER_FIELD_ELEMENT>(&data, "z");
+            let y = decode_test_data::<BYTES_PER_FIELD_ELEMENT>(&data, "y");
+            let valid = |valid: bool| std::vec![std::format!("{}", valid)];
+
+            // Inputs of the wrong length are rejected before reaching the
+            // functions, whose arguments are arrays
+            let result = match function {
+                "blob_to_kzg_commitment" => blob.map(|blob| {
+                    blob_to_kzg_commitment(&blob[0], &setup).map(|c| std::vec![hex::encode(c)])
+                }),
+                "compute_kzg_proof" => blob.zip(z).map(|(blob, z)| {
+                    compute_kzg_proof(&blob[0], &z[0], &setup)
+                        .map(|(proof, y)| std::vec![hex::encode(proof), hex::encode(y)])
+                }),
+                "compute_blob_kzg_proof" => blob.zip(commitment).map(|(blob, commitment)| {
+                    compute_blob_kzg_proof(&blob[0], &commitment[0], &setup)
+                        .map(|proof| std::vec![hex::encode(proof)])
+                }),
+                "verify_kzg_proof" => {
+                    commitment
+                        .zip(z)
+                        .zip(y)
+                        .zip(proof)
+                        .map(|(((commitment, z), y), proof)| {
+                            verify_kzg_proof(&commitment[0], &z[0], &y[0], &proof[0], &setup)
+                                .map(valid)
+                        })
+                }
+                "verify_blob_kzg_proof" => {
+                    blob.zip(commitment)
+                        .zip(proof)
+                        .map(|((blob, commitment), proof)| {
+                            verify_blob_kzg_proof(&blob[0], &commitment[0], &proof[0], &setup)
+                                .map(valid)
+                        })
+                }
+                _ => decode_test_data(&data, "blobs")
+                    .zip(decode_test_data(&data, "commitments"))
+                    .zip(decode_test_data(&data, "proofs"))
+                    .map(|((blobs, commitments), proofs)| {
+                        verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs, &setup)
+                            .map(valid)
+                    }),
+            };
+            let expected = data["output"]
+                .iter()
+                .map(|token| token.trim_start_matches("0x"))
+                .collect::<Vec<_>>();
+            match result {
+                Some(Ok(result)) => assert_eq!(result, expected, "{}", case.display()),
+                _ => assert_eq!(expected, ["null"], "{}", case.display()),
+            }
+        }
+    }
+}
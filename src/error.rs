@@ -0,0 +1,132 @@
+//! This module provides the error returned when decoding fails.
+
+use core::fmt::{self, Display, Formatter};
+
+/// The reasons decoding or parsing a field or group element can fail.
+///
+/// Unlike the [`CtOption`](subtle::CtOption) returned by functions such as
+/// `from_compressed`, this identifies the first check the input failed, and so
+/// functions returning it are not constant time for invalid inputs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecodingError {
+    /// The input does not have the expected number of bytes.
+    InvalidLength {
+        /// The number of bytes expected
+        expected: usize,
+        /// The number of bytes found
+        found: usize,
+    },
+    /// The compression or sort flag is inconsistent with the encoding.
+    InvalidFlags,
+    /// The infinity flag is set, but some of the remaining bits are not zero.
+    InfinityWithNonzeroBits,
+    /// A field element is not less than the modulus.
+    NonCanonicalField,
+    /// A string is empty, contains a character that is not a digit, or is a
+    /// decimal integer with leading zeros.
+    InvalidDigit,
+    /// The point is not on the curve.
+    NotOnCurve,
+    /// The element is not in the prime order subgroup.
+    NotInSubgroup,
+    /// The element is zero, which is not in the multiplicative group.
+    Zero,
+}
+
+impl Display for DecodingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength { expected, found } => write!(
+                f,
+                "invalid number of bytes, expected {}, found {}",
+                expected, found
+            ),
+            Self::InvalidFlags => write!(f, "invalid flag bits"),
+            Self::InfinityWithNonzeroBits => {
+                write!(f, "infinity flag set with nonzero remaining bits")
+            }
+            Self::NonCanonicalField => write!(f, "non-canonical field element"),
+            Self::InvalidDigit => write!(f, "invalid digit"),
+            Self::NotOnCurve => write!(f, "point is not on the curve"),
+            Self::NotInSubgroup => write!(f, "element is not in the prime order subgroup"),
+            Self::Zero => write!(f, "element is zero"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodingError {}
+
+impl DecodingError {
+    /// Copies `bytes` into an array, failing if it has the wrong length.
+    pub(crate) fn to_array<const N: usize>(bytes: &[u8]) -> Result<[u8; N], Self> {
+        <[u8; N]>::try_from(bytes).map_err(|_| Self::InvalidLength {
+            expected: N,
+            found: bytes.len(),
+        })
+    }
+
+    /// Checks the flags of a Zcash encoded point described in
+    /// [`notes::serialization`](crate::notes::serialization), and that the
+    /// remaining bits are zero if the infinity flag is set.
+    #[cfg(feature = "groups")]
+    pub(crate) fn check_flags(bytes: &[u8], compressed: bool) -> Result<(), Self> {
+        let compression_flag_set = (bytes[0] >> 7) & 1 == 1;
+        let infinity_flag_set = (bytes[0] >> 6) & 1 == 1;
+        let sort_flag_set = (bytes[0] >> 5) & 1 == 1;
+
+        if compression_flag_set != compressed || (sort_flag_set && !compressed) {
+            return Err(Self::InvalidFlags);
+        }
+        if infinity_flag_set
+            && (sort_flag_set || bytes[0] & 0b0001_1111 != 0 || bytes[1..].iter().any(|b| *b != 0))
+        {
+            return Err(Self::InfinityWithNonzeroBits);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "groups")]
+#[test]
+fn test_check_flags() {
+    let mut bytes = [0u8; 48];
+    assert_eq!(
+        DecodingError::check_flags(&bytes, true),
+        Err(DecodingError::InvalidFlags)
+    );
+    assert_eq!(DecodingError::check_flags(&bytes, false), Ok(()));
+
+    bytes[0] = 0b1010_0000;
+    assert_eq!(DecodingError::check_flags(&bytes, true), Ok(()));
+    assert_eq!(
+        DecodingError::check_flags(&bytes, false),
+        Err(DecodingError::InvalidFlags)
+    );
+
+    bytes[0] = 0b1100_0000;
+    assert_eq!(DecodingError::check_flags(&bytes, true), Ok(()));
+    bytes[47] = 1;
+    assert_eq!(
+        DecodingError::check_flags(&bytes, true),
+        Err(DecodingError::InfinityWithNonzeroBits)
+    );
+    bytes[47] = 0;
+    bytes[0] = 0b1110_0000;
+    assert_eq!(
+        DecodingError::check_flags(&bytes, true),
+        Err(DecodingError::InfinityWithNonzeroBits)
+    );
+}
+
+#[test]
+fn test_to_array() {
+    assert_eq!(DecodingError::to_array::<2>(&[1, 2]), Ok([1, 2]));
+    assert_eq!(
+        DecodingError::to_array::<2>(&[1, 2, 3]),
+        Err(DecodingError::InvalidLength {
+            expected: 2,
+            found: 3
+        })
+    );
+}
@@ -8,7 +8,7 @@ use core::iter::{Iterator, Product, Sum};
 use core::ops::{Add, AddAssign, BitOr, Mul, MulAssign, Neg, Sub, SubAssign};
 use elliptic_curve::{
     generic_array::{
-        typenum::{U16, U64},
+        typenum::{U16, U48, U64},
         GenericArray,
     },
     hash2curve::{
@@ -16,10 +16,14 @@ use elliptic_curve::{
         OsswuMapParams, Sgn0,
     },
 };
-use ff::Field;
+use ff::{Field, PrimeField};
+#[cfg(feature = "bits")]
+use ff::{FieldBits, PrimeFieldBits};
 use rand_core::RngCore;
 use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq, CtOption};
 
+use crate::limb32;
+use crate::safegcd::SafeGcd;
 use crate::util::{adc, mac, sbb};
 
 /// The internal representation of this type is six 64-bit unsigned
@@ -89,6 +93,15 @@ pub(crate) const MODULUS: [u64; 6] = [
     0x1a01_11ea_397f_e69a,
 ];
 
+/// The modulus followed by INV, as used by the assembly backend
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+const MODULUS_AND_INV: [u64; 7] = [
+    MODULUS[0], MODULUS[1], MODULUS[2], MODULUS[3], MODULUS[4], MODULUS[5], INV,
+];
+
+/// The modulus prepared for safegcd inversion
+const SAFEGCD: SafeGcd<7> = SafeGcd::new(&MODULUS, 381);
+
 /// INV = -(p^{-1} mod 2^64) mod 2^64
 const INV: u64 = 0x89f3_fffc_fffc_fffd;
 
@@ -122,6 +135,65 @@ const R3: Fp = Fp([
     0x0aa6_3460_9175_5d4d,
 ]);
 
+/// (p + 1) / 2, the inverse of two
+const TWO_INV: Fp = Fp([
+    0x1804_0000_0001_5554,
+    0x8550_0005_3ab0_0001,
+    0x633c_b57c_253c_276f,
+    0x6e22_d1ec_31eb_b502,
+    0xd391_6126_f2d1_4ca2,
+    0x17fb_b857_1a00_6596,
+]);
+
+/// GENERATOR = 2 (multiplicative generator of p-1 order, that is also quadratic nonresidue)
+const GENERATOR: Fp = Fp([
+    0x3213_0000_0006_554f,
+    0xb93c_0018_d6c4_0005,
+    0x5760_5e0d_b0dd_bb51,
+    0x8b25_6521_ed1f_9bcb,
+    0x6cf2_8d79_0162_2c03,
+    0x11eb_ab9d_bb81_e28c,
+]);
+
+/// p - 1 = 2^s * t, where t is odd.
+const S: u32 = 1;
+
+/// GENERATOR^t = -1, as p = 3 (mod 4)
+const ROOT_OF_UNITY: Fp = Fp([
+    0x43f5_ffff_fffc_aaae,
+    0x32b7_fff2_ed47_fffd,
+    0x07e8_3a49_a2e9_9d69,
+    0xeca8_f331_8332_bb7a,
+    0xef14_8d1e_a0f4_c069,
+    0x040a_b326_3eff_0206,
+]);
+
+/// GENERATOR^{2^s} = 4
+const DELTA: Fp = Fp([
+    0xaa27_0000_000c_fff3,
+    0x53cc_0032_fc34_000a,
+    0x478f_e97a_6b0a_807f,
+    0xb1d3_7ebe_e6ba_24d7,
+    0x8ec9_733b_bf78_ab2f,
+    0x09d6_4551_3d83_de7e,
+]);
+
+/// The modulus as u32 limbs.
+const MODULUS_LIMBS_32: [u32; 12] = [
+    0xffff_aaab,
+    0xb9fe_ffff,
+    0xb153_ffff,
+    0x1eab_fffe,
+    0xf6b0_f624,
+    0x6730_d2a0,
+    0xf385_12bf,
+    0x6477_4b84,
+    0x434b_acd7,
+    0x4b1b_a7b6,
+    0x397f_e69a,
+    0x1a01_11ea,
+];
+
 impl<'a> Neg for &'a Fp {
     type Output = Fp;
 
@@ -170,6 +242,102 @@ impl<'a, 'b> Mul<&'b Fp> for &'a Fp {
 impl_binops_additive!(Fp, Fp);
 impl_binops_multiplicative!(Fp, Fp);
 
+/// A double-width product of field elements, kept below p * 2^384 so that a
+/// single Montgomery reduction brings it back to an `Fp`. Sums and
+/// differences of products are formed in this representation to delay the
+/// reduction, which dominates the cost of multiplication in the extension
+/// fields.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct FpWide(pub(crate) [u64; 12]);
+
+impl FpWide {
+    /// Reduces this value to a field element.
+    #[inline]
+    pub(crate) const fn reduce(&self) -> Fp {
+        if limb32::ENABLED {
+            let t = limb32::montgomery_reduce::<24, 12>(
+                &limb32::to_u32(&self.0),
+                &MODULUS_LIMBS_32,
+                INV as u32,
+            );
+            Fp(limb32::to_u64(&t)).subtract_p()
+        } else {
+            let t = self.0;
+            Fp::montgomery_reduce(
+                t[0], t[1], t[2], t[3], t[4], t[5], t[6], t[7], t[8], t[9], t[10], t[11],
+            )
+        }
+    }
+
+    /// Returns self + rhs, subtracting p * 2^384 if the sum reaches it.
+    #[inline]
+    pub(crate) const fn add(&self, rhs: &FpWide) -> FpWide {
+        // The sum is below 2^766, so there is no final carry
+        let mut sum = [0u64; 12];
+        let mut carry = 0;
+        let mut i = 0;
+        while i < 12 {
+            (sum[i], carry) = adc(self.0[i], rhs.0[i], carry);
+            i += 1;
+        }
+
+        // Subtract p * 2^384 unless that underflows
+        let mut reduced = sum;
+        let mut borrow = 0;
+        let mut i = 0;
+        while i < 6 {
+            (reduced[6 + i], borrow) = sbb(sum[6 + i], MODULUS[i], borrow);
+            i += 1;
+        }
+        let mut i = 6;
+        while i < 12 {
+            reduced[i] = (sum[i] & borrow) | (reduced[i] & !borrow);
+            i += 1;
+        }
+        FpWide(reduced)
+    }
+
+    /// Returns self - rhs, adding p * 2^384 if the difference is negative.
+    #[inline]
+    pub(crate) const fn sub(&self, rhs: &FpWide) -> FpWide {
+        let mut diff = [0u64; 12];
+        let mut borrow = 0;
+        let mut i = 0;
+        while i < 12 {
+            (diff[i], borrow) = sbb(self.0[i], rhs.0[i], borrow);
+            i += 1;
+        }
+
+        let mut carry = 0;
+        let mut i = 0;
+        while i < 6 {
+            (diff[6 + i], carry) = adc(diff[6 + i], MODULUS[i] & borrow, carry);
+            i += 1;
+        }
+        FpWide(diff)
+    }
+}
+
+impl<'a, 'b> Add<&'b FpWide> for &'a FpWide {
+    type Output = FpWide;
+
+    #[inline]
+    fn add(self, rhs: &'b FpWide) -> FpWide {
+        FpWide::add(self, rhs)
+    }
+}
+
+impl<'a, 'b> Sub<&'b FpWide> for &'a FpWide {
+    type Output = FpWide;
+
+    #[inline]
+    fn sub(self, rhs: &'b FpWide) -> FpWide {
+        FpWide::sub(self, rhs)
+    }
+}
+
+impl_binops_additive_specify_output!(FpWide, FpWide, FpWide);
+
 impl FromOkm for Fp {
     type Length = U64;
 
@@ -258,7 +426,7 @@ impl OsswuMap for Fp {
         let mut tv4 = gxd.square();
         tv2 = gx1 * gxd;
         tv4 *= tv2;
-        let y1 = tv4.pow_vartime(arrayref::array_ref![Self::PARAMS.c1, 0, 6]) * tv2;
+        let y1 = tv4.pow_p_minus_3_div_4() * tv2;
         let mut x2n = tv3 * x1n;
         let mut y2 = y1 * Self::PARAMS.c2 * tv1 * self;
         tv2 = y1.square() * gxd;
@@ -318,9 +486,97 @@ impl Field for Fp {
         self.invert()
     }
 
-    fn sqrt_ratio(_num: &Self, _div: &Self) -> (Choice, Self) {
-        // ff::helpers::sqrt_ratio_generic(num, div)
-        unimplemented!()
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        // As p = 3 (mod 4), a^((p + 1) / 4) squares to either a or -a, so a
+        // single exponentiation gives the square root of a or of
+        // ROOT_OF_UNITY * a = -a, one of which is always square.
+        let a = div.invert().unwrap_or(Fp::ZERO) * num;
+        let sqrt = a.pow_p_minus_3_div_4() * a;
+        let is_square = sqrt.square().ct_eq(&a);
+
+        (is_square & !(div.is_zero() & !num.is_zero()), sqrt)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        self.sqrt()
+    }
+
+    fn is_zero_vartime(&self) -> bool {
+        self.0 == Self::ZERO.0
+    }
+}
+
+impl PrimeField for Fp {
+    /// The big-endian encoding used by [`Fp::to_bytes`].
+    type Repr = GenericArray<u8, U48>;
+
+    fn from_repr(r: Self::Repr) -> CtOption<Self> {
+        Self::from_bytes(&r.into())
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        self.to_bytes().into()
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from(self.to_bytes()[47] & 1)
+    }
+
+    const MODULUS: &'static str = "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+    const NUM_BITS: u32 = 381;
+    const CAPACITY: u32 = Self::NUM_BITS - 1;
+    const TWO_INV: Self = TWO_INV;
+    const MULTIPLICATIVE_GENERATOR: Self = GENERATOR;
+    const S: u32 = S;
+    const ROOT_OF_UNITY: Self = ROOT_OF_UNITY;
+    // -1 is its own inverse
+    const ROOT_OF_UNITY_INV: Self = ROOT_OF_UNITY;
+    const DELTA: Self = DELTA;
+}
+
+#[cfg(all(feature = "bits", not(target_pointer_width = "64")))]
+type ReprBits = [u32; 12];
+
+#[cfg(all(feature = "bits", target_pointer_width = "64"))]
+type ReprBits = [u64; 6];
+
+#[cfg(feature = "bits")]
+impl PrimeFieldBits for Fp {
+    type ReprBits = ReprBits;
+
+    fn to_le_bits(&self) -> FieldBits<Self::ReprBits> {
+        let mut bytes = self.to_bytes();
+        bytes.reverse();
+
+        #[cfg(not(target_pointer_width = "64"))]
+        let limbs = {
+            let mut limbs = [0u32; 12];
+            for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(4)) {
+                *limb = u32::from_le_bytes(chunk.try_into().unwrap());
+            }
+            limbs
+        };
+
+        #[cfg(target_pointer_width = "64")]
+        let limbs = {
+            let mut limbs = [0u64; 6];
+            for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+                *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+            }
+            limbs
+        };
+
+        FieldBits::new(limbs)
+    }
+
+    fn char_le_bits() -> FieldBits<Self::ReprBits> {
+        #[cfg(not(target_pointer_width = "64"))]
+        {
+            FieldBits::new(MODULUS_LIMBS_32)
+        }
+
+        #[cfg(target_pointer_width = "64")]
+        FieldBits::new(MODULUS)
     }
 }
 
@@ -469,6 +725,45 @@ impl Fp {
         ])
     }
 
+    /// Interprets `bytes` as a big endian integer of any length and reduces
+    /// it modulo p.
+    pub fn from_bytes_mod_order(bytes: &[u8]) -> Fp {
+        Self::from_digits_mod_order(bytes.rchunks(48).rev().map(|chunk| {
+            let mut digit = [0u8; 48];
+            digit[48 - chunk.len()..].copy_from_slice(chunk);
+            digit
+        }))
+    }
+
+    /// Interprets `bytes` as a little endian integer of any length and
+    /// reduces it modulo p.
+    pub fn from_le_bytes_mod_order(bytes: &[u8]) -> Fp {
+        Self::from_digits_mod_order(bytes.chunks(48).rev().map(|chunk| {
+            let mut digit = [0u8; 48];
+            digit[..chunk.len()].copy_from_slice(chunk);
+            digit.reverse();
+            digit
+        }))
+    }
+
+    /// Evaluates big endian 384-bit digits, most significant first, with
+    /// Horner's rule.
+    fn from_digits_mod_order(digits: impl Iterator<Item = [u8; 48]>) -> Fp {
+        // `R2` is the Montgomery form of 2^384, and as in `from_u768`, any
+        // 384-bit digit is brought into Montgomery form by multiplying by it.
+        digits.fold(Fp::ZERO, |acc, digit| {
+            let digit = Fp([
+                u64::from_be_bytes(<[u8; 8]>::try_from(&digit[40..48]).unwrap()),
+                u64::from_be_bytes(<[u8; 8]>::try_from(&digit[32..40]).unwrap()),
+                u64::from_be_bytes(<[u8; 8]>::try_from(&digit[24..32]).unwrap()),
+                u64::from_be_bytes(<[u8; 8]>::try_from(&digit[16..24]).unwrap()),
+                u64::from_be_bytes(<[u8; 8]>::try_from(&digit[8..16]).unwrap()),
+                u64::from_be_bytes(<[u8; 8]>::try_from(&digit[0..8]).unwrap()),
+            ]);
+            acc * R2 + digit * R2
+        })
+    }
+
     /// Reduces a big-endian 64-bit limb representation of a 768-bit number.
     fn from_u768(limbs: [u64; 12]) -> Fp {
         // We reduce an arbitrary 768-bit number by decomposing it into two 384-bit digits
@@ -525,21 +820,169 @@ impl Fp {
         Fp(v)
     }
 
-    /// Although this is labeled "vartime", it is only
-    /// variable time with respect to the exponent. It
-    /// is also not exposed in the public API.
-    pub(crate) fn pow_vartime(&self, by: &[u64; 6]) -> Self {
-        let mut res = Self::ONE;
-        for e in by.iter().rev() {
-            for i in (0..64).rev() {
-                res = res.square();
-
-                if ((*e >> i) & 1) == 1 {
-                    res *= self;
-                }
+    /// Raises this element to the power (p - 3) / 4 using a fixed addition
+    /// chain. The inverse, square root and Legendre symbol are all derived
+    /// from this exponent.
+    fn pow_p_minus_3_div_4(&self) -> Self {
+        #[inline(always)]
+        fn square_assign_multi(n: &mut Fp, num_times: usize) {
+            for _ in 0..num_times {
+                *n = n.square();
             }
         }
-        res
+        // sliding window of width 5 over the exponent bits
+        let x1 = *self;
+        let x2 = x1.square();
+        let x3 = x1 * x2;
+        let x5 = x3 * x2;
+        let x7 = x5 * x2;
+        let x9 = x7 * x2;
+        let x11 = x9 * x2;
+        let x13 = x11 * x2;
+        let x15 = x13 * x2;
+        let x17 = x15 * x2;
+        let x19 = x17 * x2;
+        let x21 = x19 * x2;
+        let x23 = x21 * x2;
+        let x25 = x23 * x2;
+        let x27 = x25 * x2;
+        let x29 = x27 * x2;
+        let x31 = x29 * x2;
+        let mut t = x13;
+        square_assign_multi(&mut t, 13);
+        t *= &x17;
+        square_assign_multi(&mut t, 7);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x5;
+        square_assign_multi(&mut t, 6);
+        t *= &x7;
+        square_assign_multi(&mut t, 7);
+        t *= &x23;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x25;
+        square_assign_multi(&mut t, 3);
+        t *= &x5;
+        square_assign_multi(&mut t, 6);
+        t *= &x13;
+        square_assign_multi(&mut t, 6);
+        t *= &x9;
+        square_assign_multi(&mut t, 3);
+        t *= &x3;
+        square_assign_multi(&mut t, 8);
+        t *= &x27;
+        square_assign_multi(&mut t, 3);
+        t *= &x5;
+        square_assign_multi(&mut t, 6);
+        t *= &x15;
+        square_assign_multi(&mut t, 6);
+        t *= &x27;
+        square_assign_multi(&mut t, 3);
+        t *= &x1;
+        square_assign_multi(&mut t, 8);
+        t *= &x13;
+        square_assign_multi(&mut t, 7);
+        t *= &x23;
+        square_assign_multi(&mut t, 5);
+        t *= &x11;
+        square_assign_multi(&mut t, 6);
+        t *= &x13;
+        square_assign_multi(&mut t, 6);
+        t *= &x29;
+        square_assign_multi(&mut t, 4);
+        t *= &x9;
+        square_assign_multi(&mut t, 8);
+        t *= &x29;
+        square_assign_multi(&mut t, 4);
+        t *= &x13;
+        square_assign_multi(&mut t, 7);
+        t *= &x23;
+        square_assign_multi(&mut t, 9);
+        t *= &x19;
+        square_assign_multi(&mut t, 5);
+        t *= &x25;
+        square_assign_multi(&mut t, 2);
+        t *= &x3;
+        square_assign_multi(&mut t, 7);
+        t *= &x5;
+        square_assign_multi(&mut t, 7);
+        t *= &x9;
+        square_assign_multi(&mut t, 6);
+        t *= &x23;
+        square_assign_multi(&mut t, 5);
+        t *= &x29;
+        square_assign_multi(&mut t, 5);
+        t *= &x19;
+        square_assign_multi(&mut t, 5);
+        t *= &x19;
+        square_assign_multi(&mut t, 8);
+        t *= &x13;
+        square_assign_multi(&mut t, 7);
+        t *= &x21;
+        square_assign_multi(&mut t, 9);
+        t *= &x15;
+        square_assign_multi(&mut t, 5);
+        t *= &x13;
+        square_assign_multi(&mut t, 3);
+        t *= &x3;
+        square_assign_multi(&mut t, 8);
+        t *= &x15;
+        square_assign_multi(&mut t, 3);
+        t *= &x3;
+        square_assign_multi(&mut t, 7);
+        t *= &x9;
+        square_assign_multi(&mut t, 9);
+        t *= &x15;
+        square_assign_multi(&mut t, 6);
+        t *= &x21;
+        square_assign_multi(&mut t, 6);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 4);
+        t *= &x13;
+        square_assign_multi(&mut t, 3);
+        t *= &x3;
+        square_assign_multi(&mut t, 8);
+        t *= &x21;
+        square_assign_multi(&mut t, 7);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x7;
+        square_assign_multi(&mut t, 7);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x29;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 5);
+        t *= &x31;
+        square_assign_multi(&mut t, 4);
+        t *= &x13;
+        square_assign_multi(&mut t, 6);
+        t *= &x21;
+        square_assign_multi(&mut t, 4);
+        t *= &x5;
+        square_assign_multi(&mut t, 1);
+        t
     }
 
     /// Compute the modular square root of this field element
@@ -550,14 +993,8 @@ impl Fp {
         // works for elements that are actually quadratic residue,
         // so we check that we got the correct result at the end.
 
-        let sqrt = self.pow_vartime(&[
-            0xee7f_bfff_ffff_eaab,
-            0x07aa_ffff_ac54_ffff,
-            0xd9cc_34a8_3dac_3d89,
-            0xd91d_d2e1_3ce1_44af,
-            0x92c6_e9ed_90d2_eb35,
-            0x0680_447a_8e5f_f9a6,
-        ]);
+        // (p + 1) / 4 = (p - 3) / 4 + 1
+        let sqrt = self.pow_p_minus_3_div_4() * self;
 
         CtOption::new(sqrt, sqrt.square().ct_eq(self))
     }
@@ -567,19 +1004,60 @@ impl Fp {
     /// element, returning None in the case that this element
     /// is zero.
     pub fn invert(&self) -> CtOption<Self> {
-        // Exponentiate by p - 2
-        let t = self.pow_vartime(&[
-            0xb9fe_ffff_ffff_aaa9,
-            0x1eab_fffe_b153_ffff,
-            0x6730_d2a0_f6b0_f624,
-            0x6477_4b84_f385_12bf,
-            0x4b1b_a7b6_434b_acd7,
-            0x1a01_11ea_397f_e69a,
-        ]);
+        // safegcd inverts the Montgomery form a * R to a^-1 * R^-1, and
+        // multiplying by R^3 yields a^-1 * R
+        let t = Fp(SAFEGCD.invert(&self.0)) * R3;
 
         CtOption::new(t, !self.is_zero())
     }
 
+    /// Computes the multiplicative inverse of this field element by
+    /// exponentiation, returning None in the case that this element is
+    /// zero. This is slower than [`invert`](Self::invert) and is kept as
+    /// a fallback and reference implementation.
+    pub fn invert_fermat(&self) -> CtOption<Self> {
+        // Exponentiate by p - 2 = 4 * (p - 3) / 4 + 1
+        let t = self.pow_p_minus_3_div_4().square().square() * self;
+
+        CtOption::new(t, !self.is_zero())
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged.
+    /// This uses Montgomery's trick to share one inversion between all of the
+    /// elements, allocating space for their running products. Without the
+    /// `alloc` feature this uses one inversion per small chunk instead.
+    pub fn batch_invert(values: &mut [Fp]) {
+        crate::util::batch_invert(values)
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged.
+    /// This is faster than [`batch_invert`](Self::batch_invert) but variable
+    /// time with respect to which elements are zero.
+    pub fn batch_invert_vartime(values: &mut [Fp]) {
+        crate::util::batch_invert_vartime(values)
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged,
+    /// using `scratch` for the running products so that all of the elements
+    /// share one inversion without allocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scratch` is shorter than `values`.
+    pub fn batch_invert_with_scratch(values: &mut [Fp], scratch: &mut [Fp]) {
+        crate::util::batch_invert_with_scratch(values, scratch)
+    }
+
+    /// The same as [`batch_invert_with_scratch`](Self::batch_invert_with_scratch)
+    /// but variable time with respect to which elements are zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scratch` is shorter than `values`.
+    pub fn batch_invert_vartime_with_scratch(values: &mut [Fp], scratch: &mut [Fp]) {
+        crate::util::batch_invert_vartime_with_scratch(values, scratch)
+    }
+
     #[inline]
     const fn subtract_p(&self) -> Fp {
         let (r0, borrow) = sbb(self.0[0], MODULUS[0], 0);
@@ -654,6 +1132,16 @@ impl Fp {
     /// [ePrint 2022-367](https://eprint.iacr.org/2022/367) §3.
     #[inline]
     pub(crate) fn sum_of_products<const T: usize>(a: [Fp; T], b: [Fp; T]) -> Fp {
+        if limb32::ENABLED {
+            let t = limb32::sum_of_products(
+                &a.map(|a_i| limb32::to_u32(&a_i.0)),
+                &b.map(|b_i| limb32::to_u32(&b_i.0)),
+                &MODULUS_LIMBS_32,
+                INV as u32,
+            );
+            return Fp(limb32::to_u64(&t)).subtract_p();
+        }
+
         // For a single `a x b` multiplication, operand scanning (schoolbook) takes each
         // limb of `a` in turn, and multiplies it by all of the limbs of `b` to compute
         // the result as a double-width intermediate representation, which is then fully
@@ -794,8 +1282,75 @@ impl Fp {
     }
 
     /// Compute `self` * `rhs`
+    #[cfg(not(all(feature = "asm", target_arch = "x86_64")))]
     #[inline]
     pub const fn mul(&self, rhs: &Fp) -> Fp {
+        self.mul_portable(rhs)
+    }
+
+    /// Compute `self` * `rhs`, using MULX and ADX when the CPU supports them
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    #[inline]
+    pub fn mul(&self, rhs: &Fp) -> Fp {
+        if crate::asm::has_mulx_adx() {
+            // SAFETY: the CPU supports BMI2 and ADX. Only `rhs` needs to be
+            // reduced, so `self` may be a wide integer being converted.
+            let t = unsafe { crate::asm::montgomery_mul_6(&rhs.0, &self.0, &MODULUS_AND_INV) };
+            Fp(t).subtract_p()
+        } else {
+            self.mul_portable(rhs)
+        }
+    }
+
+    /// Compute `self` * `rhs` without assembly, also in const contexts
+    #[inline]
+    pub(crate) const fn mul_portable(&self, rhs: &Fp) -> Fp {
+        if limb32::ENABLED {
+            self.mul_u32(rhs)
+        } else {
+            self.mul_u64(rhs)
+        }
+    }
+
+    /// Compute `self` * `rhs` with 32-bit limbs
+    #[inline]
+    const fn mul_u32(&self, rhs: &Fp) -> Fp {
+        let t = limb32::sum_of_products(
+            &[limb32::to_u32(&self.0)],
+            &[limb32::to_u32(&rhs.0)],
+            &MODULUS_LIMBS_32,
+            INV as u32,
+        );
+        Fp(limb32::to_u64(&t)).subtract_p()
+    }
+
+    /// Compute `self` * `rhs` with 64-bit limbs
+    #[inline]
+    const fn mul_u64(&self, rhs: &Fp) -> Fp {
+        let t = self.mul_wide_u64(rhs).0;
+        Self::montgomery_reduce(
+            t[0], t[1], t[2], t[3], t[4], t[5], t[6], t[7], t[8], t[9], t[10], t[11],
+        )
+    }
+
+    /// Compute `self` * `rhs` without Montgomery reduction. The result is
+    /// used for lazy reduction in the extension fields, and is below p * 2^384
+    /// if `self` and `rhs` are reduced.
+    #[inline]
+    pub(crate) const fn mul_wide(&self, rhs: &Fp) -> FpWide {
+        if limb32::ENABLED {
+            FpWide(limb32::to_u64(&limb32::mul_wide::<12, 24>(
+                &limb32::to_u32(&self.0),
+                &limb32::to_u32(&rhs.0),
+            )))
+        } else {
+            self.mul_wide_u64(rhs)
+        }
+    }
+
+    /// Compute `self` * `rhs` without Montgomery reduction, with 64-bit limbs
+    #[inline]
+    const fn mul_wide_u64(&self, rhs: &Fp) -> FpWide {
         let (t0, carry) = mac(0, self.0[0], rhs.0[0], 0);
         let (t1, carry) = mac(0, self.0[0], rhs.0[1], carry);
         let (t2, carry) = mac(0, self.0[0], rhs.0[2], carry);
@@ -838,12 +1393,42 @@ impl Fp {
         let (t9, carry) = mac(t9, self.0[5], rhs.0[4], carry);
         let (t10, t11) = mac(t10, self.0[5], rhs.0[5], carry);
 
-        Self::montgomery_reduce(t0, t1, t2, t3, t4, t5, t6, t7, t8, t9, t10, t11)
+        FpWide([t0, t1, t2, t3, t4, t5, t6, t7, t8, t9, t10, t11])
     }
 
     /// Squares this element.
+    #[cfg(not(all(feature = "asm", target_arch = "x86_64")))]
     #[inline]
     pub const fn square(&self) -> Self {
+        self.square_portable()
+    }
+
+    /// Squares this element, using MULX and ADX when the CPU supports them.
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    #[inline]
+    pub fn square(&self) -> Self {
+        if crate::asm::has_mulx_adx() {
+            // SAFETY: the CPU supports BMI2 and ADX
+            let t = unsafe { crate::asm::montgomery_mul_6(&self.0, &self.0, &MODULUS_AND_INV) };
+            Fp(t).subtract_p()
+        } else {
+            self.square_portable()
+        }
+    }
+
+    /// Squares this element without assembly, also in const contexts.
+    #[inline]
+    pub(crate) const fn square_portable(&self) -> Self {
+        if limb32::ENABLED {
+            self.mul_u32(self)
+        } else {
+            self.square_u64()
+        }
+    }
+
+    /// Squares this element with 64-bit limbs.
+    #[inline]
+    const fn square_u64(&self) -> Self {
         let (t1, carry) = mac(0, self.0[0], self.0[1], 0);
         let (t2, carry) = mac(0, self.0[0], self.0[2], carry);
         let (t3, carry) = mac(0, self.0[0], self.0[3], carry);
@@ -896,16 +1481,8 @@ impl Fp {
     /// using Euler's criterion
     #[inline]
     pub fn is_square(&self) -> Choice {
-        const PM1DIV2: [u64; 6] = [
-            0xdcff_7fff_ffff_d555u64,
-            0x0f55_ffff_58a9_ffffu64,
-            0xb398_6950_7b58_7b12u64,
-            0xb23b_a5c2_79c2_895fu64,
-            0x258d_d3db_21a5_d66bu64,
-            0x0d00_88f5_1cbf_f34du64,
-        ];
-
-        let res = self.pow_vartime(&PM1DIV2);
+        // (p - 1) / 2 = 2 * (p - 3) / 4 + 1
+        let res = self.pow_p_minus_3_div_4().square() * self;
         res.is_zero().bitor(res.ct_eq(&Self::ONE))
     }
 
@@ -1279,3 +1856,256 @@ fn test_zeroize() {
     a.zeroize();
     assert!(bool::from(a.is_zero()));
 }
+
+#[test]
+fn test_sqrt_ratio() {
+    let two = Fp::from(2u64);
+    let four = Fp::from(4u64);
+
+    // 4 / 2 = 2 is a nonsquare, and -2 is square
+    let (is_square, root) = Fp::sqrt_ratio(&four, &two);
+    assert!(!bool::from(is_square));
+    assert_eq!(root.square(), ROOT_OF_UNITY * two);
+
+    let (is_square, root) = Fp::sqrt_ratio(&four, &Fp::ONE);
+    assert!(bool::from(is_square));
+    assert_eq!(root.square(), four);
+
+    let (is_square, root) = Fp::sqrt_ratio(&Fp::ZERO, &two);
+    assert!(bool::from(is_square));
+    assert_eq!(root, Fp::ZERO);
+
+    let (is_square, root) = Fp::sqrt_ratio(&two, &Fp::ZERO);
+    assert!(!bool::from(is_square));
+    assert_eq!(root, Fp::ZERO);
+
+    assert_eq!(Field::sqrt(&four).unwrap().square(), four);
+    assert!(bool::from(Field::sqrt(&two).is_none()));
+}
+
+#[test]
+fn test_prime_field() {
+    assert_eq!(TWO_INV.double(), Fp::ONE);
+    assert_eq!(ROOT_OF_UNITY, -Fp::ONE);
+    assert_eq!(GENERATOR, Fp::from(2u64));
+    assert_eq!(DELTA, GENERATOR.square());
+    // 2 is a quadratic nonresidue, so GENERATOR^t = GENERATOR^((p - 1) / 2) = -1
+    assert!(bool::from(GENERATOR.sqrt().is_none()));
+
+    let a = Fp::from(0x1234_5678u64);
+    assert_eq!(Fp::from_repr(a.to_repr()).unwrap(), a);
+    assert_eq!(a.to_repr()[..], a.to_bytes()[..]);
+    assert!(!bool::from(a.is_odd()));
+    assert!(bool::from((a + Fp::ONE).is_odd()));
+
+    let mut modulus = [0u8; 48];
+    for (i, limb) in MODULUS.iter().rev().enumerate() {
+        modulus[i * 8..(i + 1) * 8].copy_from_slice(&limb.to_be_bytes());
+    }
+    assert!(bool::from(Fp::from_repr(modulus.into()).is_none()));
+    assert_eq!(
+        <Fp as PrimeField>::MODULUS,
+        format!("0x{}", hex::encode(modulus))
+    );
+}
+
+#[cfg(feature = "bits")]
+#[test]
+fn test_prime_field_bits() {
+    let a = Fp::from(0b1011u64);
+    let bits = a.to_le_bits();
+    assert!(bits[0] && bits[1] && !bits[2] && bits[3]);
+    assert_eq!(bits.iter().filter(|b| **b).count(), 3);
+
+    let a = -Fp::ONE;
+    let bits = a.to_le_bits();
+    let char_bits = Fp::char_le_bits();
+    // p - 1 differs from p only in the lowest bit
+    assert!(!bits[0] && char_bits[0]);
+    assert_eq!(bits[1..], char_bits[1..]);
+}
+
+#[test]
+fn test_batch_invert() {
+    // The algorithm is tested in util, this only checks the wrapper
+    let two = Fp::ONE + Fp::ONE;
+    let mut values = [Fp::ZERO, two];
+    Fp::batch_invert_with_scratch(&mut values, &mut [Fp::ZERO; 2]);
+    assert_eq!(values, [Fp::ZERO, two.invert().unwrap()]);
+}
+
+#[test]
+fn test_addition_chains() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    // (p - 3) / 4
+    let exp = [
+        0xee7f_bfff_ffff_eaaa,
+        0x07aa_ffff_ac54_ffff,
+        0xd9cc_34a8_3dac_3d89,
+        0xd91d_d2e1_3ce1_44af,
+        0x92c6_e9ed_90d2_eb35,
+        0x0680_447a_8e5f_f9a6,
+    ];
+    for _ in 0..20 {
+        let a = Fp::random(&mut rng);
+        assert_eq!(a.pow_p_minus_3_div_4(), a.pow_vartime(exp));
+        assert_eq!(a.invert().unwrap() * a, Fp::ONE);
+    }
+    assert_eq!(Fp::ZERO.pow_p_minus_3_div_4(), Fp::ZERO);
+    assert_eq!(Fp::ONE.pow_p_minus_3_div_4(), Fp::ONE);
+}
+
+#[test]
+fn test_invert_safegcd() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    assert!(bool::from(Fp::ZERO.invert().is_none()));
+    assert_eq!(Fp::ONE.invert().unwrap(), Fp::ONE);
+    // -1 and p - 2 exercise the largest inputs
+    assert_eq!((-Fp::ONE).invert().unwrap(), -Fp::ONE);
+    let max = Fp::from_raw_unchecked([
+        0xb9fe_ffff_ffff_aaaa,
+        0x1eab_fffe_b153_ffff,
+        0x6730_d2a0_f6b0_f624,
+        0x6477_4b84_f385_12bf,
+        0x4b1b_a7b6_434b_acd7,
+        0x1a01_11ea_397f_e69a,
+    ]);
+    assert_eq!(max.invert().unwrap(), max.invert_fermat().unwrap());
+    for _ in 0..100 {
+        let a = Fp::random(&mut rng);
+        assert_eq!(a.invert().unwrap(), a.invert_fermat().unwrap());
+    }
+}
+
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+#[test]
+fn test_asm_mul() {
+    use rand_core::SeedableRng;
+
+    if !crate::asm::has_mulx_adx() {
+        return;
+    }
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let mut values = std::vec![Fp::ZERO, Fp::ONE, -Fp::ONE, R2, R3];
+    values.extend((0..50).map(|_| Fp::random(&mut rng)));
+    for a in &values {
+        assert_eq!(a.square(), a.square_portable());
+        for b in &values {
+            assert_eq!(a.mul(b), a.mul_portable(b));
+        }
+        // the left operand may be an unreduced integer
+        let wide = Fp([u64::MAX; 6]);
+        assert_eq!(wide.mul(a), wide.mul_portable(a));
+    }
+}
+
+#[test]
+fn test_mul_u32() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let mut values = std::vec![Fp::ZERO, Fp::ONE, -Fp::ONE, R2, R3];
+    values.extend((0..50).map(|_| Fp::random(&mut rng)));
+    for a in &values {
+        assert_eq!(a.mul_u32(a), a.square_u64());
+        for b in &values {
+            assert_eq!(a.mul_u32(b), a.mul_u64(b));
+        }
+        // the left operand may be an unreduced integer
+        let wide = Fp([u64::MAX; 6]);
+        assert_eq!(wide.mul_u32(a), wide.mul_u64(a));
+    }
+
+    for chunk in values.chunks_exact(6) {
+        let a = [chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5]];
+        let b = [chunk[5], chunk[4], chunk[3], chunk[2], chunk[1], chunk[0]];
+        let t = limb32::sum_of_products(
+            &a.map(|a_i| limb32::to_u32(&a_i.0)),
+            &b.map(|b_i| limb32::to_u32(&b_i.0)),
+            &MODULUS_LIMBS_32,
+            INV as u32,
+        );
+        let expected = a
+            .iter()
+            .zip(b.iter())
+            .fold(Fp::ZERO, |acc, (a_i, b_i)| acc + a_i.mul_u64(b_i));
+        assert_eq!(Fp(limb32::to_u64(&t)).subtract_p(), expected);
+    }
+}
+
+#[test]
+fn test_mul_wide() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let mut values = std::vec![Fp::ZERO, Fp::ONE, -Fp::ONE, R2, R3];
+    values.extend((0..20).map(|_| Fp::random(&mut rng)));
+    for a in &values {
+        for b in &values {
+            let ab = a.mul_wide(b);
+            assert_eq!(ab.reduce(), a * b);
+            assert_eq!(FpWide(a.mul_wide_u64(b).0).reduce(), a * b);
+
+            // Sums and differences of products stay in range
+            let mut sum = FpWide::default();
+            let mut expected = Fp::ZERO;
+            for _ in 0..8 {
+                let t = sum + ab;
+                sum = t + t - ab;
+                expected = (expected + a * b).double() - a * b;
+            }
+            assert_eq!(sum.reduce(), expected);
+            assert_eq!((FpWide::default() - ab).reduce(), -(a * b));
+        }
+    }
+
+    // The largest representative, whose square is closest to the bound
+    let m = Fp([
+        MODULUS[0] - 1,
+        MODULUS[1],
+        MODULUS[2],
+        MODULUS[3],
+        MODULUS[4],
+        MODULUS[5],
+    ]);
+    let max = m.mul_wide(&m);
+    assert_eq!((max + max).reduce(), (m * m).double());
+    assert_eq!((FpWide::default() - max - max).reduce(), -(m * m).double());
+}
+
+#[test]
+fn test_from_bytes_mod_order() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let mut bytes = [0u8; 200];
+    rng.fill_bytes(&mut bytes);
+
+    for len in [0, 1, 32, 47, 48, 49, 64, 96, 97, 200] {
+        let be = &bytes[..len];
+        let expected = be.iter().fold(Fp::ZERO, |acc, b| {
+            acc * Fp::from(256u64) + Fp::from(*b as u64)
+        });
+        assert_eq!(Fp::from_bytes_mod_order(be), expected);
+
+        let mut le = be.to_vec();
+        le.reverse();
+        assert_eq!(Fp::from_le_bytes_mod_order(&le), expected);
+    }
+
+    let okm = <[u8; 64]>::try_from(&bytes[..64]).unwrap();
+    assert_eq!(Fp::from_bytes_mod_order(&okm), Fp::from_random_bytes(okm));
+
+    // The modulus itself, and the largest 384-bit integer
+    let mut p = [0u8; 48];
+    for (chunk, limb) in p.chunks_exact_mut(8).zip(MODULUS.iter().rev()) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    assert_eq!(Fp::from_bytes_mod_order(&p), Fp::ZERO);
+    // R2 has the value 2^384 in Montgomery form
+    assert_eq!(Fp::from_bytes_mod_order(&[0xff; 48]), R2 - Fp::ONE);
+}
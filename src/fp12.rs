@@ -1,20 +1,136 @@
+//! This module implements arithmetic over the quadratic extension field Fp12 of Fp6.
+
 use crate::fp::*;
 use crate::fp2::*;
 use crate::fp6::*;
 
+use core::convert::TryFrom;
 use core::fmt;
+use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
-use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
-
-#[cfg(feature = "pairings")]
+use ff::Field;
 use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 /// This represents an element $c_0 + c_1 w$ of $\mathbb{F}_{p^12} = \mathbb{F}_{p^6} / w^2 - v$.
 pub struct Fp12 {
+    /// The constant coefficient
     pub c0: Fp6,
+    /// The coefficient of $w$
     pub c1: Fp6,
 }
 
+/// (t - 1) / 2, where p^12 - 1 = 2^4 * t for odd t
+const TM1D2: [u64; 72] = [
+    0xfb16_3a25_2aef_aba8,
+    0x569d_8f13_009e_5c62,
+    0x276f_ae40_8bc3_a206,
+    0x1362_3959_1ff0_5220,
+    0x5527_902f_6353_38a8,
+    0x3ffb_e0db_6b5e_d612,
+    0x0558_53f2_c153_daf0,
+    0x34ce_9785_a1ec_68d3,
+    0xb798_cc0e_be87_2728,
+    0xe540_e468_7cfd_2eac,
+    0x6e0d_cb55_f201_2d35,
+    0x3788_8f9f_086d_0061,
+    0x4c9d_1aa8_7004_6938,
+    0x214c_5687_03a3_53b6,
+    0x1bab_2b33_f06f_b284,
+    0x3fa1_8c3a_5f8a_59af,
+    0x12af_6d2c_060e_010f,
+    0xce5d_72dc_08ff_afc6,
+    0xb562_c758_be3a_fb5f,
+    0x060d_ef96_ae89_38d0,
+    0xd8b2_4b24_ecdb_03cd,
+    0xce3f_5009_559b_3122,
+    0x691f_9616_5500_f154,
+    0x7b3e_65e4_db7e_af0e,
+    0xfefa_c7c6_d10a_3725,
+    0x5727_98c3_c41f_5e01,
+    0xd172_4a76_718f_0d99,
+    0xf614_8f7f_3046_0b1f,
+    0x565f_5dd2_2981_deb4,
+    0x7df2_c7ae_381e_95c0,
+    0xad3d_6090_dd1e_5fe5,
+    0xef22_041e_1682_c25f,
+    0xcff6_274a_7e4e_3d9f,
+    0xb97f_8679_7ffa_06b7,
+    0xf8fc_c685_f362_0a40,
+    0x151f_c751_c874_ffa5,
+    0x9a6c_a28e_c3f7_3398,
+    0x6eb6_e2ef_7c9d_2eb1,
+    0xb22b_0154_1342_2d41,
+    0x7ff1_e1da_8b55_f99d,
+    0x8dee_e7c3_b1f7_0cec,
+    0x2db7_502e_16f4_02cc,
+    0x8ffc_d5ee_716e_3e05,
+    0x17c1_9dfe_e84a_ae97,
+    0x9c9e_d266_bfd9_8a0f,
+    0x3358_1fc0_61a0_a329,
+    0x11bd_6537_36cc_f583,
+    0x50d9_93f2_0565_997f,
+    0xa574_5267_3cfe_6f47,
+    0x347c_2393_94d5_576d,
+    0x9b21_72d4_1ff7_b11e,
+    0x99be_32f7_e23f_abcb,
+    0x94e0_0307_d2dd_40c0,
+    0x414a_4b3d_25c6_5865,
+    0xd120_1a66_099b_b544,
+    0x414f_6cc0_b9f0_606d,
+    0xd231_b811_af52_624e,
+    0xe982_5258_c2ef_182e,
+    0x1ae2_4b2e_b1c1_12d8,
+    0x4403_aa9c_357d_a8c3,
+    0xda43_14f6_5831_6ca8,
+    0x6e03_99e1_c323_68cb,
+    0xcf41_e4de_fe60_e373,
+    0x2484_a068_2f3b_cab7,
+    0x42ad_94ae_7a62_fd28,
+    0x24af_07be_89b5_7401,
+    0x00a3_eec0_b419_9e90,
+    0x9c0b_b2e9_48d2_8b25,
+    0x59d1_c6a7_cde8_0f18,
+    0xf597_9f96_9c91_0f1c,
+    0xa3e4_d41b_98e0_c0a6,
+    0x0000_0000_000a_9d7d,
+];
+
+/// A primitive 16th root of unity, beta * w^3 = beta * v * w, where
+/// beta = sqrt(z / (u + 1)) for the primitive 8th root of unity
+/// z = (1 - u) / sqrt(-2)
+const ROOT_OF_UNITY: Fp12 = Fp12 {
+    c0: Fp6::ZERO,
+    c1: Fp6 {
+        c0: Fp2::ZERO,
+        c1: Fp2 {
+            c0: Fp::from_raw_unchecked([
+                0xeb5b_74eb_b02a_1078,
+                0xfac5_2293_2093_e24c,
+                0x10af_336b_a412_7ee9,
+                0xd363_78d3_5793_3aa7,
+                0xd390_5953_c4d5_a1c0,
+                0x03db_2c08_bdb6_cefa,
+            ]),
+            c1: Fp::from_raw_unchecked([
+                0xeb5b_74eb_b02a_1078,
+                0xfac5_2293_2093_e24c,
+                0x10af_336b_a412_7ee9,
+                0xd363_78d3_5793_3aa7,
+                0xd390_5953_c4d5_a1c0,
+                0x03db_2c08_bdb6_cefa,
+            ]),
+        },
+        c2: Fp2::ZERO,
+    },
+};
+
+/// w, which is a nonsquare as its norm -v is a nonsquare in Fp6
+const NONSQUARE: Fp12 = Fp12 {
+    c0: Fp6::ZERO,
+    c1: Fp6::ONE,
+};
+
 impl From<Fp> for Fp12 {
     fn from(f: Fp) -> Fp12 {
         Fp12 {
@@ -42,6 +158,7 @@ impl From<Fp6> for Fp12 {
     }
 }
 
+impl Eq for Fp12 {}
 impl PartialEq for Fp12 {
     fn eq(&self, other: &Fp12) -> bool {
         self.ct_eq(other).into()
@@ -88,15 +205,18 @@ impl ConstantTimeEq for Fp12 {
 }
 
 impl Fp12 {
+    /// The additive identity
     pub const ZERO: Fp12 = Fp12 {
         c0: Fp6::ZERO,
         c1: Fp6::ZERO,
     };
+    /// The multiplicative identity
     pub const ONE: Fp12 = Fp12 {
         c0: Fp6::ONE,
         c1: Fp6::ZERO,
     };
 
+    /// Returns zero, the additive identity.
     #[inline]
     #[deprecated(since = "0.5.4", note = "Use ZERO instead.")]
     pub fn zero() -> Self {
@@ -106,6 +226,7 @@ impl Fp12 {
         }
     }
 
+    /// Returns one, the multiplicative identity.
     #[inline]
     #[deprecated(since = "0.5.4", note = "Use ONE instead.")]
     pub fn one() -> Self {
@@ -115,33 +236,41 @@ impl Fp12 {
         }
     }
 
-    #[cfg(feature = "pairings")]
-    pub(crate) fn random(mut rng: impl RngCore) -> Self {
+    /// Create a random field element
+    pub fn random(mut rng: impl RngCore) -> Self {
         Fp12 {
             c0: Fp6::random(&mut rng),
             c1: Fp6::random(&mut rng),
         }
     }
 
+    /// Multiply by the sparse element $(c_0 + c_1 v) + c_4 v w$, the form of
+    /// the line evaluations in the Miller loop.
     pub fn mul_by_014(&self, c0: &Fp2, c1: &Fp2, c4: &Fp2) -> Fp12 {
-        let aa = self.c0.mul_by_01(c0, c1);
-        let bb = self.c1.mul_by_1(c4);
+        // Products are accumulated unreduced, and each coefficient is
+        // reduced once at the end.
+        let aa = self.c0.mul_by_01_wide(c0, c1);
+        let bb = self.c1.mul_by_1_wide(c4);
         let o = c1 + c4;
         let c1 = self.c1 + self.c0;
-        let c1 = c1.mul_by_01(c0, &o);
+        let c1 = c1.mul_by_01_wide(c0, &o);
         let c1 = c1 - aa - bb;
-        let c0 = bb;
-        let c0 = c0.mul_by_nonresidue();
-        let c0 = c0 + aa;
+        let c0 = bb.mul_by_nonresidue() + aa;
 
-        Fp12 { c0, c1 }
+        Fp12 {
+            c0: c0.reduce(),
+            c1: c1.reduce(),
+        }
     }
 
+    /// True if this element is the additive identity
     #[inline(always)]
     pub fn is_zero(&self) -> Choice {
         self.c0.is_zero() & self.c1.is_zero()
     }
 
+    /// Returns the conjugate $c_0 - c_1 w$, which is the inverse of elements
+    /// of the cyclotomic subgroup.
     #[inline(always)]
     pub fn conjugate(&self) -> Self {
         Fp12 {
@@ -180,20 +309,36 @@ impl Fp12 {
         Fp12 { c0, c1 }
     }
 
+    /// Squares this element
     #[inline]
     pub fn square(&self) -> Self {
-        let ab = self.c0 * self.c1;
+        let ab = self.c0.mul_wide(&self.c1);
         let c0c1 = self.c0 + self.c1;
         let c0 = self.c1.mul_by_nonresidue();
         let c0 = c0 + self.c0;
-        let c0 = c0 * c0c1;
+        let c0 = c0.mul_wide(&c0c1);
         let c0 = c0 - ab;
-        let c1 = ab + ab;
         let c0 = c0 - ab.mul_by_nonresidue();
+        let c1 = ab.reduce();
 
-        Fp12 { c0, c1 }
+        Fp12 {
+            c0: c0.reduce(),
+            c1: c1.double(),
+        }
+    }
+
+    /// Doubles this element
+    #[inline]
+    pub fn double(&self) -> Self {
+        Fp12 {
+            c0: self.c0.double(),
+            c1: self.c1.double(),
+        }
     }
 
+    /// Computes the multiplicative inverse of this field
+    /// element, returning None in the case that this element
+    /// is zero.
     pub fn invert(&self) -> CtOption<Self> {
         (self.c0.square() - self.c1.square().mul_by_nonresidue())
             .invert()
@@ -202,6 +347,114 @@ impl Fp12 {
                 c1: self.c1 * -t,
             })
     }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged.
+    /// This uses Montgomery's trick to share one inversion between all of the
+    /// elements, allocating space for their running products. Without the
+    /// `alloc` feature this uses one inversion per small chunk instead.
+    pub fn batch_invert(values: &mut [Fp12]) {
+        crate::util::batch_invert(values)
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged.
+    /// This is faster than [`batch_invert`](Self::batch_invert) but variable
+    /// time with respect to which elements are zero.
+    pub fn batch_invert_vartime(values: &mut [Fp12]) {
+        crate::util::batch_invert_vartime(values)
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged,
+    /// using `scratch` for the running products so that all of the elements
+    /// share one inversion without allocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scratch` is shorter than `values`.
+    pub fn batch_invert_with_scratch(values: &mut [Fp12], scratch: &mut [Fp12]) {
+        crate::util::batch_invert_with_scratch(values, scratch)
+    }
+
+    /// The same as [`batch_invert_with_scratch`](Self::batch_invert_with_scratch)
+    /// but variable time with respect to which elements are zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scratch` is shorter than `values`.
+    pub fn batch_invert_vartime_with_scratch(values: &mut [Fp12], scratch: &mut [Fp12]) {
+        crate::util::batch_invert_vartime_with_scratch(values, scratch)
+    }
+
+    /// Converts this element into a byte representation where each `Fp`
+    /// coefficient is written in big-endian order, starting with `c0.c0.c0`
+    /// and ending with `c1.c2.c1`.
+    pub fn to_bytes(&self) -> [u8; 576] {
+        let mut output = [0u8; 576];
+        output[..288].copy_from_slice(&self.c0.to_bytes());
+        output[288..].copy_from_slice(&self.c1.to_bytes());
+        output
+    }
+
+    /// Attempts to convert the byte representation produced by `to_bytes`
+    /// into an `Fp12`, failing if any coefficient is not canonical.
+    pub fn from_bytes(bytes: &[u8; 576]) -> CtOption<Self> {
+        let c0 = Fp6::from_bytes(&<[u8; 288]>::try_from(&bytes[..288]).unwrap());
+        let c1 = Fp6::from_bytes(&<[u8; 288]>::try_from(&bytes[288..]).unwrap());
+
+        c0.and_then(|c0| c1.map(|c1| Fp12 { c0, c1 }))
+    }
+}
+
+impl Sum for Fp12 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp12::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a Fp12> for Fp12 {
+    fn sum<I: Iterator<Item = &'a Fp12>>(iter: I) -> Self {
+        iter.fold(Fp12::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Product for Fp12 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp12::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a Fp12> for Fp12 {
+    fn product<I: Iterator<Item = &'a Fp12>>(iter: I) -> Self {
+        iter.fold(Fp12::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Field for Fp12 {
+    const ZERO: Self = Fp12::ZERO;
+    const ONE: Self = Fp12::ONE;
+
+    fn random(rng: impl RngCore) -> Self {
+        Fp12::random(rng)
+    }
+
+    fn square(&self) -> Self {
+        self.square()
+    }
+
+    fn double(&self) -> Self {
+        self.double()
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        self.invert()
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        crate::util::sqrt_ratio_generic(num, div, &NONSQUARE)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        crate::util::sqrt_tonelli_shanks(self, 4, &TM1D2, ROOT_OF_UNITY)
+    }
 }
 
 impl<'a, 'b> Mul<&'b Fp12> for &'a Fp12 {
@@ -209,17 +462,20 @@ impl<'a, 'b> Mul<&'b Fp12> for &'a Fp12 {
 
     #[inline]
     fn mul(self, other: &'b Fp12) -> Self::Output {
-        let aa = self.c0 * other.c0;
-        let bb = self.c1 * other.c1;
+        let aa = self.c0.mul_wide(&other.c0);
+        let bb = self.c1.mul_wide(&other.c1);
         let o = other.c0 + other.c1;
         let c1 = self.c1 + self.c0;
-        let c1 = c1 * o;
+        let c1 = c1.mul_wide(&o);
         let c1 = c1 - aa;
         let c1 = c1 - bb;
         let c0 = bb.mul_by_nonresidue();
         let c0 = c0 + aa;
 
-        Fp12 { c0, c1 }
+        Fp12 {
+            c0: c0.reduce(),
+            c1: c1.reduce(),
+        }
     }
 }
 
@@ -666,3 +922,77 @@ fn test_zeroize() {
     a.zeroize();
     assert!(bool::from(a.is_zero()));
 }
+
+#[test]
+fn test_field() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+
+    let mut z = ROOT_OF_UNITY;
+    for _ in 0..3 {
+        z = z.square();
+    }
+    assert_eq!(z, -Fp12::ONE);
+    assert!(bool::from(NONSQUARE.sqrt().is_none()));
+
+    for _ in 0..3 {
+        let a = Fp12::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.sqrt().unwrap().square(), b);
+        assert!(bool::from((b * NONSQUARE).sqrt().is_none()));
+
+        let (is_square, root) = Fp12::sqrt_ratio(&b, &b.square());
+        assert!(bool::from(is_square));
+        assert_eq!(root.square(), b.invert().unwrap());
+
+        assert_eq!(Fp12::from_bytes(&a.to_bytes()).unwrap(), a);
+        assert_eq!(a.double(), a + a);
+    }
+
+    assert!(bool::from(Fp12::from_bytes(&[0xff; 576]).is_none()));
+}
+
+#[test]
+fn test_batch_invert() {
+    // The algorithm is tested in util, this only checks the wrapper
+    let two = Fp12::ONE + Fp12::ONE;
+    let mut values = [Fp12::ZERO, two];
+    Fp12::batch_invert_with_scratch(&mut values, &mut [Fp12::ZERO; 2]);
+    assert_eq!(values, [Fp12::ZERO, two.invert().unwrap()]);
+}
+
+#[test]
+fn test_lazy_reduction() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+
+    for _ in 0..10 {
+        let a = Fp12::random(&mut rng);
+        let b = Fp12::random(&mut rng);
+
+        // Schoolbook multiplication over Fp6 with w^2 = v
+        let expected = Fp12 {
+            c0: a.c0 * b.c0 + (a.c1 * b.c1).mul_by_nonresidue(),
+            c1: a.c0 * b.c1 + a.c1 * b.c0,
+        };
+        assert_eq!(a * b, expected);
+        assert_eq!(a.square(), a * a);
+
+        let (c0, c1, c4) = (b.c0.c0, b.c0.c1, b.c1.c1);
+        let sparse = Fp12 {
+            c0: Fp6 {
+                c0,
+                c1,
+                c2: Fp2::ZERO,
+            },
+            c1: Fp6 {
+                c0: Fp2::ZERO,
+                c1: c4,
+                c2: Fp2::ZERO,
+            },
+        };
+        assert_eq!(a.mul_by_014(&c0, &c1, &c4), a * sparse);
+    }
+}
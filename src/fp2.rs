@@ -1,13 +1,15 @@
 //! This module implements arithmetic over the quadratic extension field Fp2.
 
 use core::fmt;
+use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use rand_core::RngCore;
 use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
-use crate::fp::Fp;
+use crate::fp::{Fp, FpWide};
 use core::convert::TryFrom;
 use elliptic_curve::hash2curve::{ExpandMsg, Expander, Sgn0};
+use ff::Field;
 
 /// A point in the multiplicative group of order p^2
 #[derive(Copy, Clone)]
@@ -112,6 +114,127 @@ impl<'a, 'b> Mul<&'b Fp2> for &'a Fp2 {
 impl_binops_additive!(Fp2, Fp2);
 impl_binops_multiplicative!(Fp2, Fp2);
 
+/// An unreduced product of `Fp2` elements, with both coefficients in the
+/// double-width representation of `FpWide`.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct Fp2Wide {
+    pub(crate) c0: FpWide,
+    pub(crate) c1: FpWide,
+}
+
+impl Fp2Wide {
+    /// Reduces this value to a field element.
+    #[inline]
+    pub(crate) fn reduce(&self) -> Fp2 {
+        Fp2 {
+            c0: self.c0.reduce(),
+            c1: self.c1.reduce(),
+        }
+    }
+
+    /// Multiply by u + 1, as in `Fp2::mul_by_nonresidue`.
+    #[inline]
+    pub(crate) fn mul_by_nonresidue(&self) -> Fp2Wide {
+        Fp2Wide {
+            c0: self.c0 - self.c1,
+            c1: self.c0 + self.c1,
+        }
+    }
+
+    /// Returns 2 * self
+    #[inline]
+    pub(crate) fn double(&self) -> Fp2Wide {
+        self + self
+    }
+}
+
+impl<'a, 'b> Add<&'b Fp2Wide> for &'a Fp2Wide {
+    type Output = Fp2Wide;
+
+    #[inline]
+    fn add(self, rhs: &'b Fp2Wide) -> Fp2Wide {
+        Fp2Wide {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+        }
+    }
+}
+
+impl<'a, 'b> Sub<&'b Fp2Wide> for &'a Fp2Wide {
+    type Output = Fp2Wide;
+
+    #[inline]
+    fn sub(self, rhs: &'b Fp2Wide) -> Fp2Wide {
+        Fp2Wide {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+        }
+    }
+}
+
+impl_binops_additive_specify_output!(Fp2Wide, Fp2Wide, Fp2Wide);
+
+impl Sum for Fp2 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp2::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a Fp2> for Fp2 {
+    fn sum<I: Iterator<Item = &'a Fp2>>(iter: I) -> Self {
+        iter.fold(Fp2::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Product for Fp2 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp2::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a Fp2> for Fp2 {
+    fn product<I: Iterator<Item = &'a Fp2>>(iter: I) -> Self {
+        iter.fold(Fp2::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Field for Fp2 {
+    const ZERO: Self = Fp2::ZERO;
+    const ONE: Self = Fp2::ONE;
+
+    fn random(rng: impl RngCore) -> Self {
+        Fp2::random(rng)
+    }
+
+    fn square(&self) -> Self {
+        self.square()
+    }
+
+    fn double(&self) -> Self {
+        self.double()
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        self.invert()
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        // u + 1 is a nonsquare
+        crate::util::sqrt_ratio_generic(
+            num,
+            div,
+            &Fp2 {
+                c0: Fp::ONE,
+                c1: Fp::ONE,
+            },
+        )
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        self.sqrt()
+    }
+}
+
 impl Sgn0 for Fp2 {
     fn sgn0(&self) -> Choice {
         if self.c0.is_zero().into() {
@@ -191,6 +314,7 @@ impl Fp2 {
     }
 
     /// Compute the square of this element
+    #[cfg(not(all(feature = "asm", target_arch = "x86_64")))]
     pub const fn square(&self) -> Self {
         // Complex squaring:
         //
@@ -214,6 +338,21 @@ impl Fp2 {
         }
     }
 
+    /// Compute the square of this element
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    pub fn square(&self) -> Self {
+        // The same as the const version, which cannot call the assembly
+        // multiplication
+        let a = (&self.c0).add(&self.c1);
+        let b = (&self.c0).sub(&self.c1);
+        let c = (&self.c0).add(&self.c0);
+
+        Fp2 {
+            c0: (&a).mul(&b),
+            c1: (&c).mul(&self.c1),
+        }
+    }
+
     /// Multiply this element by another element
     pub fn mul(&self, rhs: &Fp2) -> Self {
         // F_{p^2} x F_{p^2} multiplication implemented with operand scanning (schoolbook)
@@ -234,6 +373,33 @@ impl Fp2 {
         }
     }
 
+    /// Multiply this element by another element without reducing the
+    /// result, using Karatsuba multiplication:
+    ///
+    ///   c_0 = a_0 b_0 - a_1 b_1
+    ///   c_1 = (a_0 + a_1)(b_0 + b_1) - a_0 b_0 - a_1 b_1
+    #[inline]
+    pub(crate) fn mul_wide(&self, rhs: &Fp2) -> Fp2Wide {
+        let v0 = self.c0.mul_wide(&rhs.c0);
+        let v1 = self.c1.mul_wide(&rhs.c1);
+        let s = (self.c0 + self.c1).mul_wide(&(rhs.c0 + rhs.c1));
+
+        Fp2Wide {
+            c0: v0 - v1,
+            c1: s - v0 - v1,
+        }
+    }
+
+    /// Square this element without reducing the result, with the same
+    /// formulas as `square`.
+    #[inline]
+    pub(crate) fn square_wide(&self) -> Fp2Wide {
+        Fp2Wide {
+            c0: (self.c0 + self.c1).mul_wide(&(self.c0 - self.c1)),
+            c1: self.c0.double().mul_wide(&self.c1),
+        }
+    }
+
     /// Add self + rhs
     pub const fn add(&self, rhs: &Fp2) -> Self {
         Fp2 {
@@ -343,6 +509,42 @@ impl Fp2 {
         })
     }
 
+    /// Inverts every element of `values` in place, leaving zeros unchanged.
+    /// This uses Montgomery's trick to share one inversion between all of the
+    /// elements, allocating space for their running products. Without the
+    /// `alloc` feature this uses one inversion per small chunk instead.
+    pub fn batch_invert(values: &mut [Fp2]) {
+        crate::util::batch_invert(values)
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged.
+    /// This is faster than [`batch_invert`](Self::batch_invert) but variable
+    /// time with respect to which elements are zero.
+    pub fn batch_invert_vartime(values: &mut [Fp2]) {
+        crate::util::batch_invert_vartime(values)
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged,
+    /// using `scratch` for the running products so that all of the elements
+    /// share one inversion without allocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scratch` is shorter than `values`.
+    pub fn batch_invert_with_scratch(values: &mut [Fp2], scratch: &mut [Fp2]) {
+        crate::util::batch_invert_with_scratch(values, scratch)
+    }
+
+    /// The same as [`batch_invert_with_scratch`](Self::batch_invert_with_scratch)
+    /// but variable time with respect to which elements are zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scratch` is shorter than `values`.
+    pub fn batch_invert_vartime_with_scratch(values: &mut [Fp2], scratch: &mut [Fp2]) {
+        crate::util::batch_invert_vartime_with_scratch(values, scratch)
+    }
+
     /// Although this is labeled "vartime", it is only
     /// variable time with respect to the exponent. It
     /// is also not exposed in the public API.
@@ -935,3 +1137,46 @@ fn test_zeroize() {
     a.zeroize();
     assert!(bool::from(a.is_zero()));
 }
+
+#[test]
+fn test_sqrt_ratio() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let nonsquare = Fp2 {
+        c0: Fp::ONE,
+        c1: Fp::ONE,
+    };
+    assert!(bool::from(nonsquare.sqrt().is_none()));
+
+    for _ in 0..10 {
+        let num = Fp2::random(&mut rng);
+        let div = Fp2::random(&mut rng);
+        let ratio = num * div.invert().unwrap();
+        let (is_square, root) = Fp2::sqrt_ratio(&num, &div);
+        if bool::from(is_square) {
+            assert_eq!(root.square(), ratio);
+        } else {
+            assert_eq!(root.square(), nonsquare * ratio);
+        }
+    }
+
+    let (is_square, root) = Fp2::sqrt_ratio(&Fp2::ZERO, &nonsquare);
+    assert!(bool::from(is_square));
+    assert_eq!(root, Fp2::ZERO);
+
+    let (is_square, root) = Fp2::sqrt_ratio(&nonsquare, &Fp2::ZERO);
+    assert!(!bool::from(is_square));
+    assert_eq!(root, Fp2::ZERO);
+
+    assert_eq!([Fp2::ONE, Fp2::ONE].iter().sum::<Fp2>(), Fp2::ONE.double());
+}
+
+#[test]
+fn test_batch_invert() {
+    // The algorithm is tested in util, this only checks the wrapper
+    let two = Fp2::ONE + Fp2::ONE;
+    let mut values = [Fp2::ZERO, two];
+    Fp2::batch_invert_with_scratch(&mut values, &mut [Fp2::ZERO; 2]);
+    assert_eq!(values, [Fp2::ZERO, two.invert().unwrap()]);
+}
@@ -1,20 +1,97 @@
+//! This module implements arithmetic over the cubic extension field Fp6 of Fp2.
+
 use crate::fp::*;
 use crate::fp2::*;
 
+use core::convert::TryFrom;
 use core::fmt;
+use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
-use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
-
-#[cfg(feature = "pairings")]
+use ff::Field;
 use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 /// This represents an element $c_0 + c_1 v + c_2 v^2$ of $\mathbb{F}_{p^6} = \mathbb{F}_{p^2} / v^3 - u - 1$.
 pub struct Fp6 {
+    /// The constant coefficient
     pub c0: Fp2,
+    /// The coefficient of $v$
     pub c1: Fp2,
+    /// The coefficient of $v^2$
     pub c2: Fp2,
 }
 
+/// (t - 1) / 2, where p^6 - 1 = 2^3 * t for odd t
+const TM1D2: [u64; 36] = [
+    0xec6c_9846_3c07_05d6,
+    0x43e2_89a0_f3f4_bf2d,
+    0xbd7b_3ab5_b8c6_b958,
+    0x1e22_24a8_eb96_aa99,
+    0x5bc6_e626_bf75_d31b,
+    0x112c_3faf_ee72_8bc6,
+    0xea91_2bfa_b48a_caa3,
+    0xd110_4ac1_a5e1_d016,
+    0x8753_cc53_bc21_6c89,
+    0x68d0_e2ff_6757_720d,
+    0xceb2_9abc_f639_3273,
+    0xa48c_ffe3_6be1_9d62,
+    0x3c60_ea9e_7da8_8f87,
+    0x64a1_69ed_7be1_2645,
+    0x8ce4_91e5_9479_f2f0,
+    0xae8e_f66f_64fc_39e3,
+    0x45a0_4d8b_589e_2ee0,
+    0x6fe7_ecc0_60dc_0416,
+    0xe3a3_93c7_1fba_a2a9,
+    0x383a_e97d_6e42_a21d,
+    0xa0b0_65ad_5791_01c2,
+    0xd1d8_e1e2_4340_abd7,
+    0xdccf_5dcd_2baf_7616,
+    0x88ce_fbbc_b4b3_0a9e,
+    0x3f84_95f8_c074_54bb,
+    0xe5df_34f8_0b64_6e30,
+    0xc69f_8d8d_2694_2fd6,
+    0x7dcd_0112_c171_6c29,
+    0xd915_6853_0d98_be18,
+    0x7b7a_84c9_46d4_80f7,
+    0x5c53_8a5d_6456_a69c,
+    0x605e_c38b_8f44_1e07,
+    0xd4bf_5d87_7014_b55f,
+    0x0f22_d47e_8f4c_8a61,
+    0x9a1f_49cc_5d79_11d1,
+    0x0000_0126_e3a9_ce60,
+];
+
+/// A primitive 8th root of unity, (1 - u) / sqrt(-2), which lies in Fp2
+const ROOT_OF_UNITY: Fp6 = Fp6 {
+    c0: Fp2 {
+        c0: Fp::from_raw_unchecked([
+            0x3e2f_585d_a55c_9ad1,
+            0x4294_213d_86c1_8183,
+            0x3828_44c8_8b62_3732,
+            0x92ad_2afd_1910_3e18,
+            0x1d79_4e4f_ac7c_f0b9,
+            0x0bd5_92fc_7d82_5ec8,
+        ]),
+        c1: Fp::from_raw_unchecked([
+            0x7bcf_a7a2_5aa3_0fda,
+            0xdc17_dec1_2a92_7e7c,
+            0x2f08_8dd8_6b4e_bef1,
+            0xd1ca_2087_da74_d4a7,
+            0x2da2_5966_96ce_bc1d,
+            0x0e2b_7eed_bbfd_87d2,
+        ]),
+    },
+    c1: Fp2::ZERO,
+    c2: Fp2::ZERO,
+};
+
+/// v, which is a nonsquare as its norm u + 1 is a nonsquare in Fp2
+const NONSQUARE: Fp6 = Fp6 {
+    c0: Fp2::ZERO,
+    c1: Fp2::ONE,
+    c2: Fp2::ZERO,
+};
+
 impl From<Fp> for Fp6 {
     fn from(f: Fp) -> Fp6 {
         Fp6 {
@@ -35,6 +112,7 @@ impl From<Fp2> for Fp6 {
     }
 }
 
+impl Eq for Fp6 {}
 impl PartialEq for Fp6 {
     fn eq(&self, other: &Fp6) -> bool {
         self.ct_eq(other).into()
@@ -82,17 +160,20 @@ impl ConstantTimeEq for Fp6 {
 }
 
 impl Fp6 {
+    /// The additive identity
     pub const ZERO: Fp6 = Fp6 {
         c0: Fp2::ZERO,
         c1: Fp2::ZERO,
         c2: Fp2::ZERO,
     };
+    /// The multiplicative identity
     pub const ONE: Fp6 = Fp6 {
         c0: Fp2::ONE,
         c1: Fp2::ZERO,
         c2: Fp2::ZERO,
     };
 
+    /// Returns zero, the additive identity.
     #[inline]
     #[deprecated(since = "0.5.4", note = "Use ZERO instead.")]
     pub fn zero() -> Self {
@@ -103,6 +184,7 @@ impl Fp6 {
         }
     }
 
+    /// Returns one, the multiplicative identity.
     #[inline]
     #[deprecated(since = "0.5.4", note = "Use ONE instead.")]
     pub fn one() -> Self {
@@ -113,8 +195,8 @@ impl Fp6 {
         }
     }
 
-    #[cfg(feature = "pairings")]
-    pub(crate) fn random(mut rng: impl RngCore) -> Self {
+    /// Create a random field element
+    pub fn random(mut rng: impl RngCore) -> Self {
         Fp6 {
             c0: Fp2::random(&mut rng),
             c1: Fp2::random(&mut rng),
@@ -122,7 +204,10 @@ impl Fp6 {
         }
     }
 
+    /// Multiply by the sparse element $c_1 v$.
     pub fn mul_by_1(&self, c1: &Fp2) -> Fp6 {
+        // With no sums of products to share a reduction, reducing each
+        // product right away is faster than `mul_by_1_wide`
         Fp6 {
             c0: (self.c2 * c1).mul_by_nonresidue(),
             c1: self.c0 * c1,
@@ -130,20 +215,32 @@ impl Fp6 {
         }
     }
 
+    /// Multiply by the sparse element $c_0 + c_1 v$.
     pub fn mul_by_01(&self, c0: &Fp2, c1: &Fp2) -> Fp6 {
-        let a_a = self.c0 * c0;
-        let b_b = self.c1 * c1;
-
-        let t1 = (self.c2 * c1).mul_by_nonresidue() + a_a;
-
-        let t2 = (c0 + c1) * (self.c0 + self.c1) - a_a - b_b;
+        self.mul_by_01_wide(c0, c1).reduce()
+    }
 
-        let t3 = self.c2 * c0 + b_b;
+    /// Multiply by the sparse element $c_1 v$ without reducing the result.
+    #[inline]
+    pub(crate) fn mul_by_1_wide(&self, c1: &Fp2) -> Fp6Wide {
+        Fp6Wide {
+            c0: self.c2.mul_wide(c1).mul_by_nonresidue(),
+            c1: self.c0.mul_wide(c1),
+            c2: self.c1.mul_wide(c1),
+        }
+    }
 
-        Fp6 {
-            c0: t1,
-            c1: t2,
-            c2: t3,
+    /// Multiply by the sparse element $c_0 + c_1 v$ without reducing the
+    /// result.
+    #[inline]
+    pub(crate) fn mul_by_01_wide(&self, c0: &Fp2, c1: &Fp2) -> Fp6Wide {
+        let a_a = self.c0.mul_wide(c0);
+        let b_b = self.c1.mul_wide(c1);
+
+        Fp6Wide {
+            c0: self.c2.mul_wide(c1).mul_by_nonresidue() + a_a,
+            c1: (c0 + c1).mul_wide(&(self.c0 + self.c1)) - a_a - b_b,
+            c2: self.c2.mul_wide(c0) + b_b,
         }
     }
 
@@ -199,109 +296,64 @@ impl Fp6 {
         Fp6 { c0, c1, c2 }
     }
 
+    /// True if this element is the additive identity
     #[inline(always)]
     pub fn is_zero(&self) -> Choice {
         self.c0.is_zero() & self.c1.is_zero() & self.c2.is_zero()
     }
 
-    /// Returns `c = self * b`.
-    ///
-    /// Implements the full-tower interleaving strategy from
-    /// [ePrint 2022-376](https://eprint.iacr.org/2022/367).
+    /// Returns `self * b` without reducing the result, using Karatsuba
+    /// multiplication over `Fp2` so that each coefficient is reduced once.
     #[inline]
-    fn mul_interleaved(&self, b: &Self) -> Self {
-        // The intuition for this algorithm is that we can look at F_p^6 as a direct
-        // extension of F_p^2, and express the overall operations down to the base field
-        // F_p instead of only over F_p^2. This enables us to interleave multiplications
-        // and reductions, ensuring that we don't require double-width intermediate
-        // representations (with around twice as many limbs as F_p elements).
-
-        // We want to express the multiplication c = a x b, where a = (a_0, a_1, a_2) is
-        // an element of F_p^6, and a_i = (a_i,0, a_i,1) is an element of F_p^2. The fully
-        // expanded multiplication is given by (2022-376 §5):
-        //
-        //   c_0,0 = a_0,0 b_0,0 - a_0,1 b_0,1 + a_1,0 b_2,0 - a_1,1 b_2,1 + a_2,0 b_1,0 - a_2,1 b_1,1
-        //                                     - a_1,0 b_2,1 - a_1,1 b_2,0 - a_2,0 b_1,1 - a_2,1 b_1,0.
-        //         = a_0,0 b_0,0 - a_0,1 b_0,1 + a_1,0 (b_2,0 - b_2,1) - a_1,1 (b_2,0 + b_2,1)
-        //                                     + a_2,0 (b_1,0 - b_1,1) - a_2,1 (b_1,0 + b_1,1).
-        //
-        //   c_0,1 = a_0,0 b_0,1 + a_0,1 b_0,0 + a_1,0 b_2,1 + a_1,1 b_2,0 + a_2,0 b_1,1 + a_2,1 b_1,0
-        //                                     + a_1,0 b_2,0 - a_1,1 b_2,1 + a_2,0 b_1,0 - a_2,1 b_1,1.
-        //         = a_0,0 b_0,1 + a_0,1 b_0,0 + a_1,0(b_2,0 + b_2,1) + a_1,1(b_2,0 - b_2,1)
-        //                                     + a_2,0(b_1,0 + b_1,1) + a_2,1(b_1,0 - b_1,1).
-        //
-        //   c_1,0 = a_0,0 b_1,0 - a_0,1 b_1,1 + a_1,0 b_0,0 - a_1,1 b_0,1 + a_2,0 b_2,0 - a_2,1 b_2,1
-        //                                                                 - a_2,0 b_2,1 - a_2,1 b_2,0.
-        //         = a_0,0 b_1,0 - a_0,1 b_1,1 + a_1,0 b_0,0 - a_1,1 b_0,1 + a_2,0(b_2,0 - b_2,1)
-        //                                                                 - a_2,1(b_2,0 + b_2,1).
-        //
-        //   c_1,1 = a_0,0 b_1,1 + a_0,1 b_1,0 + a_1,0 b_0,1 + a_1,1 b_0,0 + a_2,0 b_2,1 + a_2,1 b_2,0
-        //                                                                 + a_2,0 b_2,0 - a_2,1 b_2,1
-        //         = a_0,0 b_1,1 + a_0,1 b_1,0 + a_1,0 b_0,1 + a_1,1 b_0,0 + a_2,0(b_2,0 + b_2,1)
-        //                                                                 + a_2,1(b_2,0 - b_2,1).
-        //
-        //   c_2,0 = a_0,0 b_2,0 - a_0,1 b_2,1 + a_1,0 b_1,0 - a_1,1 b_1,1 + a_2,0 b_0,0 - a_2,1 b_0,1.
-        //   c_2,1 = a_0,0 b_2,1 + a_0,1 b_2,0 + a_1,0 b_1,1 + a_1,1 b_1,0 + a_2,0 b_0,1 + a_2,1 b_0,0.
-        //
-        // Each of these is a "sum of products", which we can compute efficiently.
-
+    pub(crate) fn mul_wide(&self, b: &Self) -> Fp6Wide {
         let a = self;
-        let b10_p_b11 = b.c1.c0 + b.c1.c1;
-        let b10_m_b11 = b.c1.c0 - b.c1.c1;
-        let b20_p_b21 = b.c2.c0 + b.c2.c1;
-        let b20_m_b21 = b.c2.c0 - b.c2.c1;
+        let v0 = a.c0.mul_wide(&b.c0);
+        let v1 = a.c1.mul_wide(&b.c1);
+        let v2 = a.c2.mul_wide(&b.c2);
+
+        Fp6Wide {
+            c0: ((a.c1 + a.c2).mul_wide(&(b.c1 + b.c2)) - v1 - v2).mul_by_nonresidue() + v0,
+            c1: (a.c0 + a.c1).mul_wide(&(b.c0 + b.c1)) - v0 - v1 + v2.mul_by_nonresidue(),
+            c2: (a.c0 + a.c2).mul_wide(&(b.c0 + b.c2)) - v0 - v2 + v1,
+        }
+    }
 
-        Fp6 {
-            c0: Fp2 {
-                c0: Fp::sum_of_products(
-                    [a.c0.c0, -a.c0.c1, a.c1.c0, -a.c1.c1, a.c2.c0, -a.c2.c1],
-                    [b.c0.c0, b.c0.c1, b20_m_b21, b20_p_b21, b10_m_b11, b10_p_b11],
-                ),
-                c1: Fp::sum_of_products(
-                    [a.c0.c0, a.c0.c1, a.c1.c0, a.c1.c1, a.c2.c0, a.c2.c1],
-                    [b.c0.c1, b.c0.c0, b20_p_b21, b20_m_b21, b10_p_b11, b10_m_b11],
-                ),
-            },
-            c1: Fp2 {
-                c0: Fp::sum_of_products(
-                    [a.c0.c0, -a.c0.c1, a.c1.c0, -a.c1.c1, a.c2.c0, -a.c2.c1],
-                    [b.c1.c0, b.c1.c1, b.c0.c0, b.c0.c1, b20_m_b21, b20_p_b21],
-                ),
-                c1: Fp::sum_of_products(
-                    [a.c0.c0, a.c0.c1, a.c1.c0, a.c1.c1, a.c2.c0, a.c2.c1],
-                    [b.c1.c1, b.c1.c0, b.c0.c1, b.c0.c0, b20_p_b21, b20_m_b21],
-                ),
-            },
-            c2: Fp2 {
-                c0: Fp::sum_of_products(
-                    [a.c0.c0, -a.c0.c1, a.c1.c0, -a.c1.c1, a.c2.c0, -a.c2.c1],
-                    [b.c2.c0, b.c2.c1, b.c1.c0, b.c1.c1, b.c0.c0, b.c0.c1],
-                ),
-                c1: Fp::sum_of_products(
-                    [a.c0.c0, a.c0.c1, a.c1.c0, a.c1.c1, a.c2.c0, a.c2.c1],
-                    [b.c2.c1, b.c2.c0, b.c1.c1, b.c1.c0, b.c0.c1, b.c0.c0],
-                ),
-            },
+    /// Squares this element without reducing the result, using the CH-SQR2
+    /// formulas of Chung and Hasan.
+    #[inline]
+    pub(crate) fn square_wide(&self) -> Fp6Wide {
+        let s0 = self.c0.square_wide();
+        let s1 = self.c0.mul_wide(&self.c1).double();
+        let s2 = (self.c0 - self.c1 + self.c2).square_wide();
+        let s3 = self.c1.mul_wide(&self.c2).double();
+        let s4 = self.c2.square_wide();
+
+        Fp6Wide {
+            c0: s3.mul_by_nonresidue() + s0,
+            c1: s4.mul_by_nonresidue() + s1,
+            c2: s1 + s2 + s3 - s0 - s4,
         }
     }
 
+    /// Squares this element
     #[inline]
     pub fn square(&self) -> Self {
-        let s0 = self.c0.square();
-        let ab = self.c0 * self.c1;
-        let s1 = ab + ab;
-        let s2 = (self.c0 - self.c1 + self.c2).square();
-        let bc = self.c1 * self.c2;
-        let s3 = bc + bc;
-        let s4 = self.c2.square();
+        self.square_wide().reduce()
+    }
 
+    /// Doubles this element
+    #[inline]
+    pub fn double(&self) -> Self {
         Fp6 {
-            c0: s3.mul_by_nonresidue() + s0,
-            c1: s4.mul_by_nonresidue() + s1,
-            c2: s1 + s2 + s3 - s0 - s4,
+            c0: self.c0.double(),
+            c1: self.c1.double(),
+            c2: self.c2.double(),
         }
     }
 
+    /// Computes the multiplicative inverse of this field
+    /// element, returning None in the case that this element
+    /// is zero.
     #[inline]
     pub fn invert(&self) -> CtOption<Self> {
         let c0 = (self.c1 * self.c2).mul_by_nonresidue();
@@ -322,6 +374,103 @@ impl Fp6 {
             c2: t * c2,
         })
     }
+
+    /// Converts this element into a byte representation where each `Fp`
+    /// coefficient is written in big-endian order, starting with `c0.c0`
+    /// and ending with `c2.c1`.
+    pub fn to_bytes(&self) -> [u8; 288] {
+        let coeffs = [
+            self.c0.c0, self.c0.c1, self.c1.c0, self.c1.c1, self.c2.c0, self.c2.c1,
+        ];
+        let mut output = [0u8; 288];
+        for (chunk, c) in output.chunks_exact_mut(48).zip(coeffs.iter()) {
+            chunk.copy_from_slice(&c.to_bytes());
+        }
+        output
+    }
+
+    /// Attempts to convert the byte representation produced by `to_bytes`
+    /// into an `Fp6`, failing if any coefficient is not canonical.
+    pub fn from_bytes(bytes: &[u8; 288]) -> CtOption<Self> {
+        let mut coeffs = [Fp::ZERO; 6];
+        let mut is_some = Choice::from(1u8);
+        for (c, chunk) in coeffs.iter_mut().zip(bytes.chunks_exact(48)) {
+            let f = Fp::from_bytes(&<[u8; 48]>::try_from(chunk).unwrap());
+            is_some &= f.is_some();
+            *c = f.unwrap_or(Fp::ZERO);
+        }
+
+        CtOption::new(
+            Fp6 {
+                c0: Fp2 {
+                    c0: coeffs[0],
+                    c1: coeffs[1],
+                },
+                c1: Fp2 {
+                    c0: coeffs[2],
+                    c1: coeffs[3],
+                },
+                c2: Fp2 {
+                    c0: coeffs[4],
+                    c1: coeffs[5],
+                },
+            },
+            is_some,
+        )
+    }
+}
+
+impl Sum for Fp6 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp6::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a Fp6> for Fp6 {
+    fn sum<I: Iterator<Item = &'a Fp6>>(iter: I) -> Self {
+        iter.fold(Fp6::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Product for Fp6 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Fp6::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a Fp6> for Fp6 {
+    fn product<I: Iterator<Item = &'a Fp6>>(iter: I) -> Self {
+        iter.fold(Fp6::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Field for Fp6 {
+    const ZERO: Self = Fp6::ZERO;
+    const ONE: Self = Fp6::ONE;
+
+    fn random(rng: impl RngCore) -> Self {
+        Fp6::random(rng)
+    }
+
+    fn square(&self) -> Self {
+        self.square()
+    }
+
+    fn double(&self) -> Self {
+        self.double()
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        self.invert()
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        crate::util::sqrt_ratio_generic(num, div, &NONSQUARE)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        crate::util::sqrt_tonelli_shanks(self, 3, &TM1D2, ROOT_OF_UNITY)
+    }
 }
 
 impl<'a, 'b> Mul<&'b Fp6> for &'a Fp6 {
@@ -329,7 +478,7 @@ impl<'a, 'b> Mul<&'b Fp6> for &'a Fp6 {
 
     #[inline]
     fn mul(self, other: &'b Fp6) -> Self::Output {
-        self.mul_interleaved(other)
+        self.mul_wide(other).reduce()
     }
 }
 
@@ -384,6 +533,65 @@ impl<'a, 'b> Sub<&'b Fp6> for &'a Fp6 {
 impl_binops_additive!(Fp6, Fp6);
 impl_binops_multiplicative!(Fp6, Fp6);
 
+/// An unreduced product of `Fp6` elements, with every coefficient in the
+/// double-width representation of `FpWide`.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct Fp6Wide {
+    pub(crate) c0: Fp2Wide,
+    pub(crate) c1: Fp2Wide,
+    pub(crate) c2: Fp2Wide,
+}
+
+impl Fp6Wide {
+    /// Reduces this value to a field element.
+    #[inline]
+    pub(crate) fn reduce(&self) -> Fp6 {
+        Fp6 {
+            c0: self.c0.reduce(),
+            c1: self.c1.reduce(),
+            c2: self.c2.reduce(),
+        }
+    }
+
+    /// Multiply by v, as in `Fp6::mul_by_nonresidue`.
+    #[inline]
+    pub(crate) fn mul_by_nonresidue(&self) -> Fp6Wide {
+        Fp6Wide {
+            c0: self.c2.mul_by_nonresidue(),
+            c1: self.c0,
+            c2: self.c1,
+        }
+    }
+}
+
+impl<'a, 'b> Add<&'b Fp6Wide> for &'a Fp6Wide {
+    type Output = Fp6Wide;
+
+    #[inline]
+    fn add(self, rhs: &'b Fp6Wide) -> Fp6Wide {
+        Fp6Wide {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+            c2: self.c2 + rhs.c2,
+        }
+    }
+}
+
+impl<'a, 'b> Sub<&'b Fp6Wide> for &'a Fp6Wide {
+    type Output = Fp6Wide;
+
+    #[inline]
+    fn sub(self, rhs: &'b Fp6Wide) -> Fp6Wide {
+        Fp6Wide {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+            c2: self.c2 - rhs.c2,
+        }
+    }
+}
+
+impl_binops_additive_specify_output!(Fp6Wide, Fp6Wide, Fp6Wide);
+
 #[test]
 fn test_arithmetic() {
     use crate::fp::*;
@@ -580,3 +788,70 @@ fn test_zeroize() {
     a.zeroize();
     assert!(bool::from(a.is_zero()));
 }
+
+#[test]
+fn test_field() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+
+    assert_eq!(ROOT_OF_UNITY.square().square().square(), Fp6::ONE);
+    assert_eq!(ROOT_OF_UNITY.square().square(), -Fp6::ONE);
+    assert!(bool::from(NONSQUARE.sqrt().is_none()));
+
+    for _ in 0..5 {
+        let a = Fp6::random(&mut rng);
+        let b = a.square();
+        assert_eq!(b.sqrt().unwrap().square(), b);
+        assert!(bool::from((b * NONSQUARE).sqrt().is_none()));
+
+        let (is_square, root) = Fp6::sqrt_ratio(&NONSQUARE, &b);
+        assert!(!bool::from(is_square));
+        assert_eq!(root.square(), NONSQUARE.square() * b.invert().unwrap());
+
+        assert_eq!(Fp6::from_bytes(&a.to_bytes()).unwrap(), a);
+        assert_eq!(a.double(), a + a);
+    }
+
+    let (is_square, root) = Fp6::sqrt_ratio(&Fp6::ONE, &Fp6::ZERO);
+    assert!(!bool::from(is_square));
+    assert_eq!(root, Fp6::ZERO);
+
+    assert_eq!(Fp6::ZERO.sqrt().unwrap(), Fp6::ZERO);
+    assert!(bool::from(Fp6::from_bytes(&[0xff; 288]).is_none()));
+}
+
+#[test]
+fn test_lazy_reduction() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+
+    for _ in 0..20 {
+        let a = Fp6::random(&mut rng);
+        let b = Fp6::random(&mut rng);
+
+        // Schoolbook multiplication over Fp2 with v^3 = u + 1
+        let expected = Fp6 {
+            c0: a.c0 * b.c0 + (a.c1 * b.c2 + a.c2 * b.c1).mul_by_nonresidue(),
+            c1: a.c0 * b.c1 + a.c1 * b.c0 + (a.c2 * b.c2).mul_by_nonresidue(),
+            c2: a.c0 * b.c2 + a.c1 * b.c1 + a.c2 * b.c0,
+        };
+        assert_eq!(a * b, expected);
+        assert_eq!(a.square(), a * a);
+        assert_eq!(a.c0.square_wide().reduce(), a.c0 * a.c0);
+
+        let sparse = Fp6 {
+            c0: b.c0,
+            c1: b.c1,
+            c2: Fp2::ZERO,
+        };
+        assert_eq!(a.mul_by_01(&b.c0, &b.c1), a * sparse);
+        let sparse = Fp6 {
+            c0: Fp2::ZERO,
+            c1: b.c1,
+            c2: Fp2::ZERO,
+        };
+        assert_eq!(a.mul_by_1(&b.c1), a * sparse);
+    }
+}
@@ -14,14 +14,17 @@ use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
 #[cfg(feature = "alloc")]
 use group::WnafGroup;
 
+use crate::arkworks::{decode_flags, encode_flags, fp_from_le_bytes, reverse_fp_chunks, Validate};
 use crate::fp::Fp;
 use crate::util::decode_hex_into_slice;
-use crate::Scalar;
+use crate::DecodingError;
+use crate::{Bls12381G1, Scalar};
 use elliptic_curve::consts::U48;
 use elliptic_curve::generic_array::GenericArray;
 use elliptic_curve::hash2curve::{ExpandMsgXmd, Sgn0};
 use elliptic_curve::ops::{LinearCombination, MulByGenerator};
 use elliptic_curve::point::AffineCoordinates;
+use elliptic_curve::sec1::{Coordinates, EncodedPoint, FromEncodedPoint, ToEncodedPoint};
 use elliptic_curve::{
     group::cofactor::CofactorGroup,
     hash2curve::{ExpandMsg, MapToCurve},
@@ -38,7 +41,7 @@ use elliptic_curve::{
 pub struct G1Affine {
     pub(crate) x: Fp,
     pub(crate) y: Fp,
-    infinity: Choice,
+    pub(crate) infinity: Choice,
 }
 
 impl Default for G1Affine {
@@ -100,15 +103,8 @@ impl From<G1Projective> for G1Affine {
 impl_from_bytes!(
     G1Affine,
     |p: &G1Affine| p.to_compressed(),
-    |arr: &[u8]| {
-        let tmp = <[u8; G1Affine::COMPRESSED_BYTES]>::try_from(arr).map_err(|_| {
-            alloc::format!(
-                "Invalid number of bytes for G1Affine, expected {}, found {}",
-                G1Affine::COMPRESSED_BYTES,
-                arr.len()
-            )
-        })?;
-        Ok::<CtOption<G1Affine>, alloc::string::String>(G1Affine::from_compressed(&tmp))
+    |arr: &[u8]| -> Result<G1Affine, DecodingError> {
+        G1Affine::try_from_compressed(&DecodingError::to_array(arr)?)
     }
 );
 
@@ -230,7 +226,7 @@ impl AffineCoordinates for G1Affine {
     type FieldRepr = GenericArray<u8, U48>;
 
     fn x(&self) -> Self::FieldRepr {
-        GenericArray::<u8, U48>::clone_from_slice(&self.x.to_bytes())
+        self.x.to_bytes().into()
     }
 
     fn y_is_odd(&self) -> Choice {
@@ -238,6 +234,67 @@ impl AffineCoordinates for G1Affine {
     }
 }
 
+impl ToEncodedPoint<Bls12381G1> for G1Affine {
+    fn to_encoded_point(&self, compress: bool) -> EncodedPoint<Bls12381G1> {
+        if bool::from(self.infinity) {
+            EncodedPoint::<Bls12381G1>::identity()
+        } else {
+            EncodedPoint::<Bls12381G1>::from_affine_coordinates(
+                &self.x(),
+                &self.y.to_bytes().into(),
+                compress,
+            )
+        }
+    }
+}
+
+impl FromEncodedPoint<Bls12381G1> for G1Affine {
+    /// Attempts to decode a SEC1 encoded point, checking that it is on the
+    /// curve and in the correct subgroup. Compact points are not supported.
+    fn from_encoded_point(point: &EncodedPoint<Bls12381G1>) -> CtOption<Self> {
+        let fp = |bytes: &GenericArray<u8, U48>| Fp::from_bytes(&(*bytes).into());
+        match point.coordinates() {
+            Coordinates::Identity => CtOption::new(Self::identity(), Choice::from(1)),
+            Coordinates::Compact { .. } => CtOption::new(Self::identity(), Choice::from(0)),
+            Coordinates::Compressed { x, y_is_odd } => fp(x).and_then(|x| {
+                // Recover a y-coordinate given x by y = sqrt(x^3 + 4)
+                ((x.square() * x) + B).sqrt().and_then(|y| {
+                    let y =
+                        Fp::conditional_select(&y, &-y, y.sgn0() ^ Choice::from(y_is_odd as u8));
+                    let p = G1Affine {
+                        x,
+                        y,
+                        infinity: Choice::from(0),
+                    };
+                    CtOption::new(p, p.is_torsion_free())
+                })
+            }),
+            Coordinates::Uncompressed { x, y } => fp(x).and_then(|x| {
+                fp(y).and_then(|y| {
+                    let p = G1Affine {
+                        x,
+                        y,
+                        infinity: Choice::from(0),
+                    };
+                    CtOption::new(p, p.is_on_curve() & p.is_torsion_free())
+                })
+            }),
+        }
+    }
+}
+
+impl ToEncodedPoint<Bls12381G1> for G1Projective {
+    fn to_encoded_point(&self, compress: bool) -> EncodedPoint<Bls12381G1> {
+        G1Affine::from(self).to_encoded_point(compress)
+    }
+}
+
+impl FromEncodedPoint<Bls12381G1> for G1Projective {
+    fn from_encoded_point(point: &EncodedPoint<Bls12381G1>) -> CtOption<Self> {
+        G1Affine::from_encoded_point(point).map(Self::from)
+    }
+}
+
 impl G1Affine {
     /// Bytes to represent this point compressed
     pub const COMPRESSED_BYTES: usize = 48;
@@ -449,6 +506,138 @@ impl G1Affine {
         })
     }
 
+    /// Attempts to deserialize a compressed element, returning the reason it
+    /// is invalid on failure. See [`notes::serialization`](crate::notes::serialization)
+    /// for details about how group elements are serialized.
+    pub fn try_from_compressed(bytes: &[u8; 48]) -> Result<Self, DecodingError> {
+        Option::from(Self::from_compressed(bytes)).ok_or_else(|| {
+            if let Err(e) = DecodingError::check_flags(bytes, true) {
+                return e;
+            }
+
+            let mut tmp = *bytes;
+            tmp[0] &= 0b0001_1111;
+            match Option::<Fp>::from(Fp::from_bytes(&tmp)) {
+                None => DecodingError::NonCanonicalField,
+                Some(x) if bool::from(((x.square() * x) + B).sqrt().is_none()) => {
+                    DecodingError::NotOnCurve
+                }
+                Some(_) => DecodingError::NotInSubgroup,
+            }
+        })
+    }
+
+    /// Attempts to deserialize an uncompressed element, returning the reason it
+    /// is invalid on failure. See [`notes::serialization`](crate::notes::serialization)
+    /// for details about how group elements are serialized.
+    pub fn try_from_uncompressed(bytes: &[u8; 96]) -> Result<Self, DecodingError> {
+        Option::from(Self::from_uncompressed(bytes)).ok_or_else(|| {
+            if let Err(e) = DecodingError::check_flags(bytes, false) {
+                return e;
+            }
+
+            match Option::<Self>::from(Self::from_uncompressed_unchecked(bytes)) {
+                None => DecodingError::NonCanonicalField,
+                Some(p) if !bool::from(p.is_on_curve()) => DecodingError::NotOnCurve,
+                Some(_) => DecodingError::NotInSubgroup,
+            }
+        })
+    }
+
+    /// Serializes this element into compressed form using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks).
+    pub fn to_compressed_le(&self) -> [u8; 48] {
+        let x = Fp::conditional_select(&self.x, &Fp::ZERO, self.infinity);
+
+        let mut res = [0u8; 48];
+        res[..48].copy_from_slice(&x.to_bytes());
+        reverse_fp_chunks(&mut res);
+        res[48 - 1] |= encode_flags(self.infinity, self.y.lexicographically_largest());
+        res
+    }
+
+    /// Serializes this element into uncompressed form using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks).
+    pub fn to_uncompressed_le(&self) -> [u8; 96] {
+        let x = Fp::conditional_select(&self.x, &Fp::ZERO, self.infinity);
+        let y = Fp::conditional_select(&self.y, &Fp::ONE, self.infinity);
+
+        let mut res = [0u8; 96];
+        res[..48].copy_from_slice(&x.to_bytes());
+        res[48..].copy_from_slice(&y.to_bytes());
+        reverse_fp_chunks(&mut res);
+        res[96 - 1] |= encode_flags(self.infinity, Choice::from(0u8));
+        res
+    }
+
+    /// Attempts to deserialize a compressed element using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks), checking that it
+    /// is in the correct subgroup if `validate` is [`Validate::Yes`].
+    pub fn from_compressed_le(bytes: &[u8; 48], validate: Validate) -> CtOption<Self> {
+        let mut tmp = *bytes;
+        let (flags_valid, infinity_flag_set, largest_flag_set) = decode_flags(&mut tmp);
+
+        fp_from_le_bytes(&tmp[..48]).and_then(|x| {
+            CtOption::new(G1Affine::identity(), infinity_flag_set & x.is_zero())
+                .or_else(|| {
+                    // Recover a y-coordinate given x by y = sqrt(x^3 + 4)
+                    ((x.square() * x) + B).sqrt().and_then(|y| {
+                        // Switch to the correct y-coordinate if necessary.
+                        let y = Fp::conditional_select(
+                            &y,
+                            &-y,
+                            y.lexicographically_largest() ^ largest_flag_set,
+                        );
+
+                        CtOption::new(
+                            G1Affine {
+                                x,
+                                y,
+                                infinity: Choice::from(0u8),
+                            },
+                            !infinity_flag_set,
+                        )
+                    })
+                })
+                .and_then(|p| {
+                    CtOption::new(p, flags_valid & (!validate.choice() | p.is_torsion_free()))
+                })
+        })
+    }
+
+    /// Attempts to deserialize an uncompressed element using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks), checking that it
+    /// is on the curve and in the correct subgroup if `validate` is
+    /// [`Validate::Yes`].
+    pub fn from_uncompressed_le(bytes: &[u8; 96], validate: Validate) -> CtOption<Self> {
+        let mut tmp = *bytes;
+        let (flags_valid, infinity_flag_set, largest_flag_set) = decode_flags(&mut tmp);
+
+        fp_from_le_bytes(&tmp[..48]).and_then(|x| {
+            fp_from_le_bytes(&tmp[48..]).and_then(|y| {
+                let p = G1Affine::conditional_select(
+                    &G1Affine {
+                        x,
+                        y,
+                        infinity: Choice::from(0u8),
+                    },
+                    &G1Affine::identity(),
+                    infinity_flag_set,
+                );
+
+                CtOption::new(
+                    p,
+                    flags_valid &
+                    // The y-coordinate is stored in full, so it has no sign flag.
+                    (!largest_flag_set) &
+                    // If the infinity flag is set, the point should have been (0, 1).
+                    (!infinity_flag_set | (x.is_zero() & y.ct_eq(&Fp::ONE))) &
+                    (!validate.choice() | (p.is_on_curve() & p.is_torsion_free())),
+                )
+            })
+        })
+    }
+
     /// Attempts to deserialize a compressed element hex string. See [`notes::serialization`](crate::notes::serialization)
     /// for details about how group elements are serialized.
     pub fn from_compressed_hex(hex: &str) -> CtOption<Self> {
@@ -562,15 +751,8 @@ impl From<G1Affine> for G1Projective {
 impl_from_bytes!(
     G1Projective,
     |p: &G1Projective| p.to_compressed(),
-    |arr: &[u8]| {
-        let tmp = <[u8; G1Projective::COMPRESSED_BYTES]>::try_from(arr).map_err(|_| {
-            alloc::format!(
-                "Invalid number of bytes for G1Projective, expected {}, found {}",
-                G1Projective::COMPRESSED_BYTES,
-                arr.len()
-            )
-        })?;
-        Ok::<CtOption<G1Projective>, alloc::string::String>(G1Projective::from_compressed(&tmp))
+    |arr: &[u8]| -> Result<G1Projective, DecodingError> {
+        G1Affine::try_from_compressed(&DecodingError::to_array(arr)?).map(G1Projective::from)
     }
 );
 
@@ -1115,17 +1297,7 @@ impl PartialEq for G1Compressed {
 }
 
 impl_from_bytes!(G1Compressed, |p: &G1Compressed| p.0, |arr: &[u8]| {
-    let tmp = <[u8; G1Projective::COMPRESSED_BYTES]>::try_from(arr).map_err(|_| {
-        alloc::format!(
-            "Invalid number of bytes for G1Projective, expected {}, found {}",
-            G1Projective::COMPRESSED_BYTES,
-            arr.len()
-        )
-    })?;
-    Ok::<CtOption<G1Compressed>, alloc::string::String>(CtOption::new(
-        G1Compressed(tmp),
-        Choice::from(1u8),
-    ))
+    DecodingError::to_array(arr).map(G1Compressed)
 });
 
 /// Group1 in it's Uncompressed from
@@ -1165,17 +1337,7 @@ impl ConstantTimeEq for G1Uncompressed {
 }
 
 impl_from_bytes!(G1Uncompressed, |p: &G1Uncompressed| p.0, |arr: &[u8]| {
-    let tmp = <[u8; G1Projective::UNCOMPRESSED_BYTES]>::try_from(arr).map_err(|_| {
-        alloc::format!(
-            "Invalid number of bytes for G1Projective, expected {}, found {}",
-            G1Projective::UNCOMPRESSED_BYTES,
-            arr.len()
-        )
-    })?;
-    Ok::<CtOption<G1Uncompressed>, alloc::string::String>(CtOption::new(
-        G1Uncompressed(tmp),
-        Choice::from(1u8),
-    ))
+    DecodingError::to_array(arr).map(G1Uncompressed)
 });
 
 impl Eq for G1Uncompressed {}
@@ -2124,3 +2286,217 @@ fn test_hex() {
     assert_eq!(g2.is_some().unwrap_u8(), 1u8);
     assert_eq!(g1, g2.unwrap());
 }
+
+#[test]
+fn test_try_from_bytes() {
+    let g = G1Affine::generator();
+    assert_eq!(G1Affine::try_from_compressed(&g.to_compressed()), Ok(g));
+    assert_eq!(G1Affine::try_from_uncompressed(&g.to_uncompressed()), Ok(g));
+
+    let mut bytes = g.to_compressed();
+    bytes[0] &= 0b0111_1111;
+    assert_eq!(
+        G1Affine::try_from_compressed(&bytes),
+        Err(DecodingError::InvalidFlags)
+    );
+    let mut bytes = g.to_uncompressed();
+    bytes[0] |= 0b0010_0000;
+    assert_eq!(
+        G1Affine::try_from_uncompressed(&bytes),
+        Err(DecodingError::InvalidFlags)
+    );
+
+    let mut bytes = G1Affine::identity().to_compressed();
+    bytes[47] = 1;
+    assert_eq!(
+        G1Affine::try_from_compressed(&bytes),
+        Err(DecodingError::InfinityWithNonzeroBits)
+    );
+    let mut bytes = G1Affine::identity().to_uncompressed();
+    bytes[95] = 1;
+    assert_eq!(
+        G1Affine::try_from_uncompressed(&bytes),
+        Err(DecodingError::InfinityWithNonzeroBits)
+    );
+
+    let mut bytes = [0xffu8; 48];
+    bytes[0] = 0b1001_1111;
+    assert_eq!(
+        G1Affine::try_from_compressed(&bytes),
+        Err(DecodingError::NonCanonicalField)
+    );
+    let mut bytes = g.to_uncompressed();
+    bytes[48..].copy_from_slice(&[0xff; 48]);
+    assert_eq!(
+        G1Affine::try_from_uncompressed(&bytes),
+        Err(DecodingError::NonCanonicalField)
+    );
+
+    // Find an x-coordinate which is not on the curve
+    let mut x = Fp::ONE;
+    while bool::from(((x.square() * x) + B).sqrt().is_some()) {
+        x += Fp::ONE;
+    }
+    let mut bytes = x.to_bytes();
+    bytes[0] |= 0b1000_0000;
+    assert_eq!(
+        G1Affine::try_from_compressed(&bytes),
+        Err(DecodingError::NotOnCurve)
+    );
+    let mut bytes = g.to_uncompressed();
+    bytes[95] ^= 1;
+    assert_eq!(
+        G1Affine::try_from_uncompressed(&bytes),
+        Err(DecodingError::NotOnCurve)
+    );
+
+    // (0, 2) is on the curve but not in the subgroup
+    let p = G1Affine {
+        x: Fp::ZERO,
+        y: Fp::ONE + Fp::ONE,
+        infinity: Choice::from(0u8),
+    };
+    assert_eq!(
+        G1Affine::try_from_compressed(&p.to_compressed()),
+        Err(DecodingError::NotInSubgroup)
+    );
+    assert_eq!(
+        G1Affine::try_from_uncompressed(&p.to_uncompressed()),
+        Err(DecodingError::NotInSubgroup)
+    );
+
+    assert_eq!(
+        G1Affine::try_from(&[0u8; 47][..]),
+        Err(DecodingError::InvalidLength {
+            expected: 48,
+            found: 47
+        })
+    );
+}
+
+#[test]
+fn test_le_encoding() {
+    // The ark-serialize 0.3 encodings, computed from the serialization rules
+    // of ark-ec 0.3 rather than with this crate
+    let g = G1Affine::generator();
+    let vectors = [
+        (
+            g,
+            "bbc622db0af03afbef1a7af93fe8556c58ac1b173f3a4ea105b974974f8c68c30faca94f8c63952694d79731a7d3f117",
+            "bbc622db0af03afbef1a7af93fe8556c58ac1b173f3a4ea105b974974f8c68c30faca94f8c63952694d79731a7d3f117e1e7c5462923aa0ce48a88a244c73cd0edb3042ccb18db00f60ad0d595e0f5fce48a1d74ed309ea0f1a0aae381f4b308",
+        ),
+        (
+            -g,
+            "bbc622db0af03afbef1a7af93fe8556c58ac1b173f3a4ea105b974974f8c68c30faca94f8c63952694d79731a7d3f197",
+            "bbc622db0af03afbef1a7af93fe8556c58ac1b173f3a4ea105b974974f8c68c30faca94f8c63952694d79731a7d3f117cac239b9d6dc54ad1b75cb0eba386f4e3642accad5b95566c907b51def6a8167f2212ecfc8767daaa845d555681d4d11",
+        ),
+        (
+            G1Affine::identity(),
+            "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040",
+            "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040",
+        ),
+    ];
+    for (p, compressed, uncompressed) in vectors {
+        assert_eq!(hex::encode(p.to_compressed_le()), compressed);
+        assert_eq!(hex::encode(p.to_uncompressed_le()), uncompressed);
+        let compressed = <[u8; 48]>::try_from(hex::decode(compressed).unwrap()).unwrap();
+        let uncompressed = <[u8; 96]>::try_from(hex::decode(uncompressed).unwrap()).unwrap();
+        assert_eq!(
+            G1Affine::from_compressed_le(&compressed, Validate::Yes).unwrap(),
+            p
+        );
+        assert_eq!(
+            G1Affine::from_uncompressed_le(&uncompressed, Validate::Yes).unwrap(),
+            p
+        );
+    }
+
+    // The uncompressed encoding has no sign flag, and the identity is (0, 1)
+    let mut bytes = (-g).to_uncompressed_le();
+    bytes[95] |= 0b1000_0000;
+    assert!(bool::from(
+        G1Affine::from_uncompressed_le(&bytes, Validate::No).is_none()
+    ));
+    let mut bytes = [0u8; 96];
+    bytes[95] = 0b0100_0000;
+    assert!(bool::from(
+        G1Affine::from_uncompressed_le(&bytes, Validate::No).is_none()
+    ));
+
+    // Both flags set
+    let mut bytes = g.to_compressed_le();
+    bytes[47] |= 0b1100_0000;
+    assert!(bool::from(
+        G1Affine::from_compressed_le(&bytes, Validate::No).is_none()
+    ));
+
+    // Infinity with a nonzero coordinate
+    let mut bytes = g.to_compressed_le();
+    bytes[47] |= 0b0100_0000;
+    assert!(bool::from(
+        G1Affine::from_compressed_le(&bytes, Validate::No).is_none()
+    ));
+
+    // (0, 2) is on the curve but not in the subgroup
+    let p = G1Affine {
+        x: Fp::ZERO,
+        y: Fp::ONE + Fp::ONE,
+        infinity: Choice::from(0u8),
+    };
+    assert!(bool::from(
+        G1Affine::from_compressed_le(&p.to_compressed_le(), Validate::Yes).is_none()
+    ));
+    assert_eq!(
+        G1Affine::from_compressed_le(&p.to_compressed_le(), Validate::No).unwrap(),
+        p
+    );
+    assert!(bool::from(
+        G1Affine::from_uncompressed_le(&p.to_uncompressed_le(), Validate::Yes).is_none()
+    ));
+
+    // Not on the curve
+    let mut bytes = g.to_uncompressed_le();
+    bytes[48] ^= 1;
+    assert!(bool::from(
+        G1Affine::from_uncompressed_le(&bytes, Validate::Yes).is_none()
+    ));
+    assert!(bool::from(
+        G1Affine::from_uncompressed_le(&bytes, Validate::No).is_some()
+    ));
+}
+
+#[test]
+fn test_sec1_encoding() {
+    let g = G1Affine::generator();
+    for p in [g, -g, (g * Scalar::from(7u64)).to_affine()] {
+        for compress in [false, true] {
+            let encoded = p.to_encoded_point(compress);
+            assert_eq!(encoded.is_compressed(), compress);
+            assert_eq!(encoded.len(), if compress { 49 } else { 97 });
+            assert_eq!(encoded.x().unwrap()[..], p.to_uncompressed()[..48]);
+            assert_eq!(G1Affine::from_encoded_point(&encoded).unwrap(), p);
+            assert_eq!(
+                G1Projective::from_encoded_point(&encoded).unwrap(),
+                G1Projective::from(p)
+            );
+        }
+    }
+
+    let identity = G1Affine::identity().to_encoded_point(true);
+    assert!(identity.is_identity());
+    assert!(bool::from(
+        G1Affine::from_encoded_point(&identity)
+            .unwrap()
+            .is_identity()
+    ));
+
+    // (0, 2) is on the curve but not in the subgroup, and (0, 1) is not on the curve.
+    let mut y = [0u8; 48];
+    y[47] = 2;
+    let x = [0u8; 48];
+    let bad = EncodedPoint::<Bls12381G1>::from_affine_coordinates(&x.into(), &y.into(), false);
+    assert!(bool::from(G1Affine::from_encoded_point(&bad).is_none()));
+    y[47] = 1;
+    let bad = EncodedPoint::<Bls12381G1>::from_affine_coordinates(&x.into(), &y.into(), false);
+    assert!(bool::from(G1Affine::from_encoded_point(&bad).is_none()));
+}
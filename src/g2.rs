@@ -14,9 +14,11 @@ use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTi
 #[cfg(feature = "alloc")]
 use group::WnafGroup;
 
+use crate::arkworks::{decode_flags, encode_flags, fp2_from_le_bytes, reverse_fp_chunks, Validate};
 use crate::fp::Fp;
 use crate::fp2::Fp2;
 use crate::util::decode_hex_into_slice;
+use crate::DecodingError;
 use crate::Scalar;
 use elliptic_curve::consts::U96;
 use elliptic_curve::generic_array::GenericArray;
@@ -38,7 +40,7 @@ use elliptic_curve::{
 pub struct G2Affine {
     pub(crate) x: Fp2,
     pub(crate) y: Fp2,
-    infinity: Choice,
+    pub(crate) infinity: Choice,
 }
 
 impl Default for G2Affine {
@@ -100,15 +102,8 @@ impl From<G2Projective> for G2Affine {
 impl_from_bytes!(
     G2Affine,
     |p: &G2Affine| p.to_compressed(),
-    |arr: &[u8]| {
-        let tmp = <[u8; G2Affine::COMPRESSED_BYTES]>::try_from(arr).map_err(|_| {
-            alloc::format!(
-                "Invalid number of bytes for G2Affine, expected {}, found {}",
-                G2Affine::COMPRESSED_BYTES,
-                arr.len()
-            )
-        })?;
-        Ok::<CtOption<G2Affine>, alloc::string::String>(G2Affine::from_compressed(&tmp))
+    |arr: &[u8]| -> Result<G2Affine, DecodingError> {
+        G2Affine::try_from_compressed(&DecodingError::to_array(arr)?)
     }
 );
 
@@ -525,6 +520,157 @@ impl G2Affine {
         })
     }
 
+    /// Attempts to deserialize a compressed element, returning the reason it
+    /// is invalid on failure. See [`notes::serialization`](crate::notes::serialization)
+    /// for details about how group elements are serialized.
+    pub fn try_from_compressed(
+        bytes: &[u8; Self::COMPRESSED_BYTES],
+    ) -> Result<Self, DecodingError> {
+        Option::from(Self::from_compressed(bytes)).ok_or_else(|| {
+            if let Err(e) = DecodingError::check_flags(bytes, true) {
+                return e;
+            }
+
+            let mut xc1 = [0u8; 48];
+            xc1.copy_from_slice(&bytes[0..48]);
+            xc1[0] &= 0b0001_1111;
+            let mut xc0 = [0u8; 48];
+            xc0.copy_from_slice(&bytes[48..96]);
+            let xc0 = Fp::from_bytes(&xc0);
+            let x = Fp::from_bytes(&xc1).and_then(|c1| xc0.map(|c0| Fp2 { c0, c1 }));
+            match Option::<Fp2>::from(x) {
+                None => DecodingError::NonCanonicalField,
+                Some(x) if bool::from(((x.square() * x) + B).sqrt().is_none()) => {
+                    DecodingError::NotOnCurve
+                }
+                Some(_) => DecodingError::NotInSubgroup,
+            }
+        })
+    }
+
+    /// Attempts to deserialize an uncompressed element, returning the reason it
+    /// is invalid on failure. See [`notes::serialization`](crate::notes::serialization)
+    /// for details about how group elements are serialized.
+    pub fn try_from_uncompressed(
+        bytes: &[u8; Self::UNCOMPRESSED_BYTES],
+    ) -> Result<Self, DecodingError> {
+        Option::from(Self::from_uncompressed(bytes)).ok_or_else(|| {
+            if let Err(e) = DecodingError::check_flags(bytes, false) {
+                return e;
+            }
+
+            match Option::<Self>::from(Self::from_uncompressed_unchecked(bytes)) {
+                None => DecodingError::NonCanonicalField,
+                Some(p) if !bool::from(p.is_on_curve()) => DecodingError::NotOnCurve,
+                Some(_) => DecodingError::NotInSubgroup,
+            }
+        })
+    }
+
+    /// Serializes this element into compressed form using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks).
+    pub fn to_compressed_le(&self) -> [u8; Self::COMPRESSED_BYTES] {
+        let x = Fp2::conditional_select(&self.x, &Fp2::ZERO, self.infinity);
+
+        let mut res = [0u8; Self::COMPRESSED_BYTES];
+        res[..48].copy_from_slice(&x.c0.to_bytes());
+        res[48..].copy_from_slice(&x.c1.to_bytes());
+        reverse_fp_chunks(&mut res);
+        res[Self::COMPRESSED_BYTES - 1] |=
+            encode_flags(self.infinity, self.y.lexicographically_largest());
+        res
+    }
+
+    /// Serializes this element into uncompressed form using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks).
+    pub fn to_uncompressed_le(&self) -> [u8; Self::UNCOMPRESSED_BYTES] {
+        let x = Fp2::conditional_select(&self.x, &Fp2::ZERO, self.infinity);
+        let y = Fp2::conditional_select(&self.y, &Fp2::ONE, self.infinity);
+
+        let mut res = [0u8; Self::UNCOMPRESSED_BYTES];
+        res[..48].copy_from_slice(&x.c0.to_bytes());
+        res[48..96].copy_from_slice(&x.c1.to_bytes());
+        res[96..144].copy_from_slice(&y.c0.to_bytes());
+        res[144..].copy_from_slice(&y.c1.to_bytes());
+        reverse_fp_chunks(&mut res);
+        res[Self::UNCOMPRESSED_BYTES - 1] |= encode_flags(self.infinity, Choice::from(0u8));
+        res
+    }
+
+    /// Attempts to deserialize a compressed element using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks), checking that it
+    /// is in the correct subgroup if `validate` is [`Validate::Yes`].
+    pub fn from_compressed_le(
+        bytes: &[u8; Self::COMPRESSED_BYTES],
+        validate: Validate,
+    ) -> CtOption<Self> {
+        let mut tmp = *bytes;
+        let (flags_valid, infinity_flag_set, largest_flag_set) = decode_flags(&mut tmp);
+
+        fp2_from_le_bytes(&tmp[..96]).and_then(|x| {
+            CtOption::new(G2Affine::identity(), infinity_flag_set & x.is_zero())
+                .or_else(|| {
+                    // Recover a y-coordinate given x by y = sqrt(x^3 + 4(u + 1))
+                    ((x.square() * x) + B).sqrt().and_then(|y| {
+                        // Switch to the correct y-coordinate if necessary.
+                        let y = Fp2::conditional_select(
+                            &y,
+                            &-y,
+                            y.lexicographically_largest() ^ largest_flag_set,
+                        );
+
+                        CtOption::new(
+                            G2Affine {
+                                x,
+                                y,
+                                infinity: Choice::from(0u8),
+                            },
+                            !infinity_flag_set,
+                        )
+                    })
+                })
+                .and_then(|p| {
+                    CtOption::new(p, flags_valid & (!validate.choice() | p.is_torsion_free()))
+                })
+        })
+    }
+
+    /// Attempts to deserialize an uncompressed element using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks), checking that it
+    /// is on the curve and in the correct subgroup if `validate` is
+    /// [`Validate::Yes`].
+    pub fn from_uncompressed_le(
+        bytes: &[u8; Self::UNCOMPRESSED_BYTES],
+        validate: Validate,
+    ) -> CtOption<Self> {
+        let mut tmp = *bytes;
+        let (flags_valid, infinity_flag_set, largest_flag_set) = decode_flags(&mut tmp);
+
+        fp2_from_le_bytes(&tmp[..96]).and_then(|x| {
+            fp2_from_le_bytes(&tmp[96..]).and_then(|y| {
+                let p = G2Affine::conditional_select(
+                    &G2Affine {
+                        x,
+                        y,
+                        infinity: Choice::from(0u8),
+                    },
+                    &G2Affine::identity(),
+                    infinity_flag_set,
+                );
+
+                CtOption::new(
+                    p,
+                    flags_valid &
+                    // The y-coordinate is stored in full, so it has no sign flag.
+                    (!largest_flag_set) &
+                    // If the infinity flag is set, the point should have been (0, 1).
+                    (!infinity_flag_set | (x.is_zero() & y.ct_eq(&Fp2::ONE))) &
+                    (!validate.choice() | (p.is_on_curve() & p.is_torsion_free())),
+                )
+            })
+        })
+    }
+
     /// Attempts to deserialize a compressed element hex string. See [`notes::serialization`](crate::notes::serialization)
     /// for details about how group elements are serialized.
     pub fn from_compressed_hex(hex: &str) -> CtOption<Self> {
@@ -617,15 +763,8 @@ impl From<G2Affine> for G2Projective {
 impl_from_bytes!(
     G2Projective,
     |p: &G2Projective| p.to_compressed(),
-    |arr: &[u8]| {
-        let tmp = <[u8; G2Projective::COMPRESSED_BYTES]>::try_from(arr).map_err(|_| {
-            alloc::format!(
-                "Invalid number of bytes for G2Projective, expected {}, found {}",
-                G2Projective::COMPRESSED_BYTES,
-                arr.len()
-            )
-        })?;
-        Ok::<CtOption<G2Projective>, alloc::string::String>(G2Projective::from_compressed(&tmp))
+    |arr: &[u8]| -> Result<G2Projective, DecodingError> {
+        G2Affine::try_from_compressed(&DecodingError::to_array(arr)?).map(G2Projective::from)
     }
 );
 
@@ -1162,7 +1301,7 @@ impl G2Projective {
     }
 
     /// simplified swu map for q = 9 mod 16 where AB == 0
-    fn sswu_map(u: &Fp2) -> Self {
+    pub(crate) fn sswu_map(u: &Fp2) -> Self {
         const A: Fp2 = Fp2 {
             c0: Fp::ZERO,
             c1: Fp([
@@ -1267,7 +1406,7 @@ impl G2Projective {
     }
 
     /// Computes the isogeny map for this point
-    fn isogeny_map(&self) -> Self {
+    pub(crate) fn isogeny_map(&self) -> Self {
         use crate::isogeny::g2::*;
 
         fn compute(xxs: &[Fp2], k: &[Fp2]) -> Fp2 {
@@ -1341,17 +1480,7 @@ impl PartialEq for G2Compressed {
 }
 
 impl_from_bytes!(G2Compressed, |p: &G2Compressed| p.0, |arr: &[u8]| {
-    let tmp = <[u8; G2Affine::COMPRESSED_BYTES]>::try_from(arr).map_err(|_| {
-        alloc::format!(
-            "Invalid number of bytes for G2Compressed, expected {}, found {}",
-            G2Affine::COMPRESSED_BYTES,
-            arr.len()
-        )
-    })?;
-    Ok::<CtOption<G2Compressed>, alloc::string::String>(CtOption::new(
-        G2Compressed(tmp),
-        Choice::from(1u8),
-    ))
+    DecodingError::to_array(arr).map(G2Compressed)
 });
 
 /// Group2 in it's uncompressed form
@@ -1391,17 +1520,7 @@ impl ConstantTimeEq for G2Uncompressed {
 }
 
 impl_from_bytes!(G2Uncompressed, |p: &G2Uncompressed| p.0, |arr: &[u8]| {
-    let tmp = <[u8; G2Affine::UNCOMPRESSED_BYTES]>::try_from(arr).map_err(|_| {
-        alloc::format!(
-            "Invalid number of bytes for G2Uncompressed, expected {}, found {}",
-            G2Affine::UNCOMPRESSED_BYTES,
-            arr.len()
-        )
-    })?;
-    Ok::<CtOption<G2Uncompressed>, alloc::string::String>(CtOption::new(
-        G2Uncompressed(tmp),
-        Choice::from(1u8),
-    ))
+    DecodingError::to_array(arr).map(G2Uncompressed)
 });
 
 impl Eq for G2Uncompressed {}
@@ -2671,3 +2790,132 @@ fn test_hex() {
     assert_eq!(g2.is_some().unwrap_u8(), 1u8);
     assert_eq!(g1, g2.unwrap());
 }
+
+#[test]
+fn test_try_from_bytes() {
+    let g = G2Affine::generator();
+    assert_eq!(G2Affine::try_from_compressed(&g.to_compressed()), Ok(g));
+    assert_eq!(G2Affine::try_from_uncompressed(&g.to_uncompressed()), Ok(g));
+
+    let mut bytes = g.to_uncompressed();
+    bytes[0] |= 0b1000_0000;
+    assert_eq!(
+        G2Affine::try_from_uncompressed(&bytes),
+        Err(DecodingError::InvalidFlags)
+    );
+
+    let mut bytes = G2Affine::identity().to_compressed();
+    bytes[95] = 1;
+    assert_eq!(
+        G2Affine::try_from_compressed(&bytes),
+        Err(DecodingError::InfinityWithNonzeroBits)
+    );
+
+    let mut bytes = g.to_compressed();
+    bytes[48..].copy_from_slice(&[0xff; 48]);
+    assert_eq!(
+        G2Affine::try_from_compressed(&bytes),
+        Err(DecodingError::NonCanonicalField)
+    );
+
+    // Find x-coordinates which are on and not on the curve
+    let mut on_curve = None;
+    let mut off_curve = None;
+    let mut x = Fp2::ONE;
+    while on_curve.is_none() || off_curve.is_none() {
+        if bool::from(((x.square() * x) + B).sqrt().is_some()) {
+            on_curve = Some(x);
+        } else {
+            off_curve = Some(x);
+        }
+        x += Fp2::ONE;
+    }
+    let encode = |x: Fp2| {
+        let mut bytes = [0u8; 96];
+        bytes[..48].copy_from_slice(&x.c1.to_bytes());
+        bytes[48..].copy_from_slice(&x.c0.to_bytes());
+        bytes[0] |= 0b1000_0000;
+        bytes
+    };
+    assert_eq!(
+        G2Affine::try_from_compressed(&encode(off_curve.unwrap())),
+        Err(DecodingError::NotOnCurve)
+    );
+    assert_eq!(
+        G2Affine::try_from_compressed(&encode(on_curve.unwrap())),
+        Err(DecodingError::NotInSubgroup)
+    );
+
+    let mut bytes = g.to_uncompressed();
+    bytes[191] ^= 1;
+    assert_eq!(
+        G2Affine::try_from_uncompressed(&bytes),
+        Err(DecodingError::NotOnCurve)
+    );
+}
+
+#[test]
+fn test_le_encoding() {
+    // The ark-serialize 0.3 encodings, computed from the serialization rules
+    // of ark-ec 0.3 rather than with this crate
+    let g = G2Affine::generator();
+    let vectors = [
+        (
+            g,
+            "b8bd21c1c85680d4efbb05a82603ac0b77d1e37a640b51b4023b40fad47ae4c65110c52d27050826910a8ff0b2a24a027e2b045d057dace5575d941312f14c3349507fdcbb61dab51ab62099d0d06b59654f2788a0d3ac7d609f7152602be013",
+            "b8bd21c1c85680d4efbb05a82603ac0b77d1e37a640b51b4023b40fad47ae4c65110c52d27050826910a8ff0b2a24a027e2b045d057dace5575d941312f14c3349507fdcbb61dab51ab62099d0d06b59654f2788a0d3ac7d609f7152602be0130128b808865493e189a2ac3bccc93a922cd16051699a426da7d3bd8caa9bfdad1a352edac6cdc98c116e7d7227d5e50cbe795ff05f07a9aaa11dec5c270d373fab992e57ab927426af63a7857e283ecb998bc22bb0d2ac32cc34a72ea0c40606",
+        ),
+        (
+            -g,
+            "b8bd21c1c85680d4efbb05a82603ac0b77d1e37a640b51b4023b40fad47ae4c65110c52d27050826910a8ff0b2a24a027e2b045d057dace5575d941312f14c3349507fdcbb61dab51ab62099d0d06b59654f2788a0d3ac7d609f7152602be093",
+            "b8bd21c1c85680d4efbb05a82603ac0b77d1e37a640b51b4023b40fad47ae4c65110c52d27050826910a8ff0b2a24a027e2b045d057dace5575d941312f14c3349507fdcbb61dab51ab62099d0d06b59654f2788a0d3ac7d609f7152602be013aa8247f779ab6bd8755da7753236718cf72450a53738eef9173fc766daaf79b6bc771d69efd951be887802c7c23c1b0ded30a00fa0f8550f5ee26754d7f274df785c829ff53fbc4010afdd6d062339993d21891706d56e18ceb1d80a4a4dfa13",
+        ),
+        (
+            G2Affine::identity(),
+            "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040",
+            "000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040",
+        ),
+    ];
+    for (p, compressed, uncompressed) in vectors {
+        assert_eq!(hex::encode(p.to_compressed_le()), compressed);
+        assert_eq!(hex::encode(p.to_uncompressed_le()), uncompressed);
+        let compressed = <[u8; 96]>::try_from(hex::decode(compressed).unwrap()).unwrap();
+        let uncompressed = <[u8; 192]>::try_from(hex::decode(uncompressed).unwrap()).unwrap();
+        assert_eq!(
+            G2Affine::from_compressed_le(&compressed, Validate::Yes).unwrap(),
+            p
+        );
+        assert_eq!(
+            G2Affine::from_uncompressed_le(&uncompressed, Validate::Yes).unwrap(),
+            p
+        );
+    }
+
+    let p = G2Affine::from(G2Projective::GENERATOR * Scalar::from(7u64));
+    assert_eq!(
+        G2Affine::from_compressed_le(&p.to_compressed_le(), Validate::Yes).unwrap(),
+        p
+    );
+    assert_eq!(
+        G2Affine::from_uncompressed_le(&p.to_uncompressed_le(), Validate::Yes).unwrap(),
+        p
+    );
+
+    // The uncompressed encoding has no sign flag, and the identity is (0, 1)
+    let mut bytes = (-g).to_uncompressed_le();
+    bytes[191] |= 0b1000_0000;
+    assert!(bool::from(
+        G2Affine::from_uncompressed_le(&bytes, Validate::No).is_none()
+    ));
+    let mut bytes = [0u8; 192];
+    bytes[191] = 0b0100_0000;
+    assert!(bool::from(
+        G2Affine::from_uncompressed_le(&bytes, Validate::No).is_none()
+    ));
+
+    let mut bytes = g.to_uncompressed_le();
+    bytes[100] ^= 1;
+    assert!(bool::from(
+        G2Affine::from_uncompressed_le(&bytes, Validate::Yes).is_none()
+    ));
+}
@@ -0,0 +1,641 @@
+//! This module provides the KZG polynomial commitment scheme over
+//! $\mathbb{G}_1$, with proofs verified against $\mathbb{G}_2$.
+//!
+//! A structured reference string ([`Srs`]) holds the points `τ^i G1` of the
+//! monomial basis, the points `L_i(τ) G1` of the Lagrange basis of an
+//! [`EvaluationDomain`], or both, together with `G2` and `τ G2` for some
+//! secret τ. Here `G1` and `G2` are the standard generators, and L_i are the
+//! Lagrange basis polynomials of the domain.
+//!
+//! A commitment to a polynomial p is `C = p(τ) G1`, and a proof that
+//! `p(z) = y` is `π = q(τ) G1` for the quotient `q = (p - y) / (X - z)`,
+//! checked with the pairing equation `e(C - y G1 + z π, G2) = e(π, τ G2)`.
+//! Polynomials may be given by their coefficients, as a [`Polynomial`], or by
+//! their evaluations over the domain of a Lagrange basis.
+//!
+//! Commitments and proofs are constant time with respect to the polynomials.
+//! Proofs for polynomials given by evaluations are variable time with respect
+//! to whether the point is in the domain, and interpolation and verification
+//! are variable time.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use ff::Field;
+use group::Group;
+use rand_core::RngCore;
+
+use crate::{
+    multi_miller_loop, DecodingError, EvaluationDomain, G1Affine, G1Projective, G2Affine,
+    G2Prepared, G2Projective, Polynomial, Scalar,
+};
+
+/// The basis of the $\mathbb{G}_1$ points of a structured reference string.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Basis {
+    /// The points `τ^i G1`, which commit to polynomials of degree less than
+    /// their number.
+    Monomial,
+    /// The points `L_i(τ) G1` for the Lagrange basis polynomials L_i of the
+    /// domain of size equal to their number, in natural order.
+    Lagrange,
+}
+
+/// A structured reference string for KZG commitments.
+#[derive(Clone, Debug)]
+pub struct Srs {
+    /// τ^i G1, which may be empty
+    g1_monomial: Vec<G1Projective>,
+    /// L_i(τ) G1, which is empty if `domain` is `None`
+    g1_lagrange: Vec<G1Projective>,
+    domain: Option<EvaluationDomain>,
+    /// τ^i G2, with at least two points
+    g2_monomial: Vec<G2Projective>,
+    g2_prepared: G2Prepared,
+    tau_g2_prepared: G2Prepared,
+}
+
+impl Srs {
+    /// Creates a reference string from the $\mathbb{G}_1$ points `g1` in the
+    /// given basis and the points `g2_monomial` = `τ^i G2`.
+    ///
+    /// The points are not checked to share the same τ. Only `G2` and `τ G2`
+    /// are needed to verify single point proofs, while proofs for k points
+    /// need k + 1 points of $\mathbb{G}_2$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `g1` is empty, if `g2_monomial` has fewer than two points,
+    /// or if `g1` is in the Lagrange basis and its length is not a power of
+    /// two.
+    pub fn new(basis: Basis, g1: &[G1Affine], g2_monomial: &[G2Affine]) -> Self {
+        assert!(!g1.is_empty(), "the setup has no G1 points");
+        assert!(
+            g2_monomial.len() >= 2,
+            "the setup has fewer than two G2 points"
+        );
+
+        let g1 = g1.iter().map(G1Projective::from).collect::<Vec<_>>();
+        let (g1_monomial, g1_lagrange, domain) = match basis {
+            Basis::Monomial => (g1, Vec::new(), None),
+            Basis::Lagrange => {
+                assert!(
+                    g1.len().is_power_of_two(),
+                    "the number of Lagrange basis points must be a power of two"
+                );
+                let domain = EvaluationDomain::new(g1.len());
+                (Vec::new(), g1, domain)
+            }
+        };
+
+        Self {
+            g1_monomial,
+            g1_lagrange,
+            domain,
+            g2_monomial: g2_monomial.iter().map(G2Projective::from).collect(),
+            g2_prepared: G2Prepared::from(g2_monomial[0]),
+            tau_g2_prepared: G2Prepared::from(g2_monomial[1]),
+        }
+    }
+
+    /// Decodes a reference string from the concatenated compressed encodings
+    /// of its $\mathbb{G}_1$ points in the given basis and of its points
+    /// `τ^i G2`, checking that every point is valid. See [`new`](Self::new)
+    /// for the requirements on the number of points.
+    pub fn from_compressed(basis: Basis, g1: &[u8], g2: &[u8]) -> Result<Self, DecodingError> {
+        let g1_points = g1.len() / G1Affine::COMPRESSED_BYTES;
+        let g1_points = match basis {
+            Basis::Monomial => g1_points.max(1),
+            Basis::Lagrange => g1_points.max(1).next_power_of_two(),
+        };
+        if g1.len() != g1_points * G1Affine::COMPRESSED_BYTES {
+            return Err(DecodingError::InvalidLength {
+                expected: g1_points * G1Affine::COMPRESSED_BYTES,
+                found: g1.len(),
+            });
+        }
+        let g2_points = (g2.len() / G2Affine::COMPRESSED_BYTES).max(2);
+        if g2.len() != g2_points * G2Affine::COMPRESSED_BYTES {
+            return Err(DecodingError::InvalidLength {
+                expected: g2_points * G2Affine::COMPRESSED_BYTES,
+                found: g2.len(),
+            });
+        }
+
+        let g1 = g1
+            .chunks_exact(G1Affine::COMPRESSED_BYTES)
+            .map(|bytes| G1Affine::try_from_compressed(&DecodingError::to_array(bytes)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        let g2 = g2
+            .chunks_exact(G2Affine::COMPRESSED_BYTES)
+            .map(|bytes| G2Affine::try_from_compressed(&DecodingError::to_array(bytes)?))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(basis, &g1, &g2))
+    }
+
+    /// Creates a reference string with `g1_len` monomial basis points and
+    /// `g2_len` points of $\mathbb{G}_2$ from a known secret τ.
+    ///
+    /// Anyone who knows τ can forge proofs, so this is only suitable for
+    /// testing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `g1_len` is zero or `g2_len` is less than two.
+    pub fn insecure_from_secret(tau: &Scalar, g1_len: usize, g2_len: usize) -> Self {
+        let mut powers = vec![Scalar::ONE; g1_len.max(g2_len)];
+        for i in 1..powers.len() {
+            powers[i] = powers[i - 1] * tau;
+        }
+
+        let g1 = powers[..g1_len]
+            .iter()
+            .map(|p| G1Affine::from(G1Projective::GENERATOR * p))
+            .collect::<Vec<_>>();
+        let g2 = powers[..g2_len]
+            .iter()
+            .map(|p| G2Affine::from(G2Projective::GENERATOR * p))
+            .collect::<Vec<_>>();
+        Self::new(Basis::Monomial, &g1, &g2)
+    }
+
+    /// Adds the Lagrange basis of the domain of size 2^`log_size`, computed
+    /// from the monomial basis, replacing any existing Lagrange basis.
+    ///
+    /// This takes about (n / 2) log_2 n + n point multiplications.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than 2^`log_size` monomial basis points.
+    pub fn with_lagrange_basis(mut self, log_size: u32) -> Self {
+        let domain = EvaluationDomain::from_log_size(log_size)
+            .filter(|d| d.size() <= self.g1_monomial.len())
+            .expect("there are not enough monomial basis points");
+        self.g1_lagrange = domain
+            .ifft_g1(&self.g1_monomial[..domain.size()])
+            .iter()
+            .map(G1Projective::from)
+            .collect();
+        self.domain = Some(domain);
+        self
+    }
+
+    /// Returns the number of monomial basis points, which is one more than
+    /// the largest degree of a polynomial that can be committed to in that
+    /// basis.
+    pub fn monomial_len(&self) -> usize {
+        self.g1_monomial.len()
+    }
+
+    /// Returns the domain of the Lagrange basis, if there is one.
+    pub fn domain(&self) -> Option<&EvaluationDomain> {
+        self.domain.as_ref()
+    }
+
+    /// Commits to a polynomial, using the monomial basis if there is one and
+    /// the Lagrange basis otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the degree of `p` is too large for the basis.
+    pub fn commit(&self, p: &Polynomial) -> G1Affine {
+        if self.g1_monomial.is_empty() {
+            let domain = self.lagrange_domain(p.coefficients().len());
+            return self.commit_lagrange(&p.evaluate_over_domain(domain));
+        }
+        assert!(
+            p.coefficients().len() <= self.g1_monomial.len(),
+            "the degree of the polynomial is too large for the setup"
+        );
+        G1Affine::from(G1Projective::sum_of_products(
+            &self.g1_monomial[..p.coefficients().len()],
+            p.coefficients(),
+        ))
+    }
+
+    /// Commits to the polynomial of degree less than n with the values
+    /// `evaluations` over the domain of size n = `evaluations.len()`, using
+    /// the Lagrange basis if it has that domain and the monomial basis
+    /// otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `evaluations` is not a power of two or is too
+    /// large for the basis.
+    pub fn commit_evaluations(&self, evaluations: &[Scalar]) -> G1Affine {
+        match self.domain_of(evaluations) {
+            Some(_) => self.commit_lagrange(evaluations),
+            None => self.commit(&Self::interpolate(evaluations)),
+        }
+    }
+
+    /// Opens a polynomial at `z`, returning the proof and the value `p(z)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the degree of `p` is too large for the basis.
+    pub fn open(&self, p: &Polynomial, z: &Scalar) -> (G1Affine, Scalar) {
+        let (quotient, value) = p.divide_by_linear(z);
+        (self.commit(&quotient), value)
+    }
+
+    /// Opens the polynomial with the values `evaluations` over the domain of
+    /// size `evaluations.len()` at `z`, returning the proof and the value of
+    /// the polynomial at `z`. See [`commit_evaluations`](Self::commit_evaluations).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `evaluations` is not a power of two or is too
+    /// large for the basis.
+    pub fn open_evaluations(&self, evaluations: &[Scalar], z: &Scalar) -> (G1Affine, Scalar) {
+        let Some(domain) = self.domain_of(evaluations) else {
+            return self.open(&Self::interpolate(evaluations), z);
+        };
+
+        // q(ω^i) = (p(ω^i) - y) / (ω^i - z), except at z itself
+        let mut quotient = domain.elements().map(|w| w - z).collect::<Vec<_>>();
+        let position = quotient.iter().position(|d| bool::from(d.is_zero()));
+        Scalar::batch_invert_with_scratch(&mut quotient, &mut vec![Scalar::ZERO; domain.size()]);
+        let value = match position {
+            Some(m) => evaluations[m],
+            // The barycentric formula p(z) = (z^n - 1) / n * sum p(ω^i) ω^i / (z - ω^i),
+            // reusing the inverses above
+            None => {
+                let sum = quotient
+                    .iter()
+                    .zip(evaluations)
+                    .zip(domain.elements())
+                    .fold(Scalar::ZERO, |acc, ((q, e), w)| acc + q * e * w);
+                -sum * domain.evaluate_vanishing_polynomial(z) * domain.size_inv()
+            }
+        };
+        for (q, e) in quotient.iter_mut().zip(evaluations) {
+            *q *= e - value;
+        }
+
+        // If z = ω^m, then q(z) = p'(z) = -z^-1 sum_{i != m} q(ω^i) ω^i
+        if let Some(m) = position {
+            let sum = quotient
+                .iter()
+                .zip(domain.elements())
+                .fold(Scalar::ZERO, |acc, (q, w)| acc + q * w);
+            quotient[m] = -sum * z.invert().unwrap();
+        }
+        (self.commit_lagrange(&quotient), value)
+    }
+
+    /// Checks a proof that the polynomial committed to by `commitment` has
+    /// the value `value` at `z`.
+    pub fn verify(
+        &self,
+        commitment: &G1Affine,
+        z: &Scalar,
+        value: &Scalar,
+        proof: &G1Affine,
+    ) -> bool {
+        // e(C - y G1 + z π, G2) = e(π, τ G2)
+        let lhs = G1Affine::from(commitment - G1Projective::GENERATOR * value + proof * z);
+        self.check(&lhs, proof)
+    }
+
+    /// Opens a polynomial at several points with a single proof, returning
+    /// the proof and the values of `p` at `points`, or `None` if two points
+    /// are equal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the degree of `p` is too large for the basis.
+    pub fn open_multi(&self, p: &Polynomial, points: &[Scalar]) -> Option<(G1Affine, Vec<Scalar>)> {
+        let values = p.evaluate_many(points);
+        let remainder = Polynomial::interpolate(points, &values)?;
+
+        // p - I is divisible by Z, one linear factor at a time
+        let mut quotient = p - &remainder;
+        for x in points {
+            quotient = quotient.divide_by_linear(x).0;
+        }
+        Some((self.commit(&quotient), values))
+    }
+
+    /// Checks a proof that the polynomial committed to by `commitment` has
+    /// the values `values` at `points`, computed by
+    /// [`open_multi`](Self::open_multi).
+    ///
+    /// This is false if two points are equal, or if there are not more
+    /// points of $\mathbb{G}_2$ than points to check.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` and `values` have different lengths.
+    pub fn verify_multi(
+        &self,
+        commitment: &G1Affine,
+        points: &[Scalar],
+        values: &[Scalar],
+        proof: &G1Affine,
+    ) -> bool {
+        let Some(remainder) = Polynomial::interpolate(points, values) else {
+            return false;
+        };
+        if points.len() >= self.g2_monomial.len() {
+            return false;
+        }
+
+        // e(C - I(τ) G1, G2) = e(π, Z(τ) G2)
+        let vanishing = vanishing_polynomial(points);
+        let vanishing_g2 = G2Affine::from(G2Projective::sum_of_products(
+            &self.g2_monomial[..vanishing.coefficients().len()],
+            vanishing.coefficients(),
+        ));
+        let lhs = G1Affine::from(G1Projective::from(commitment) - self.commit(&remainder));
+        multi_miller_loop(&[
+            (&lhs, &self.g2_prepared),
+            (&-proof, &G2Prepared::from(vanishing_g2)),
+        ])
+        .final_exponentiation()
+        .is_identity()
+        .into()
+    }
+
+    /// Checks many single point proofs at once, where the `i`th proof
+    /// claims that the polynomial committed to by `commitments[i]` has the
+    /// value `values[i]` at `points[i]`.
+    ///
+    /// This checks a random linear combination of the pairing equations, so
+    /// it needs one multi-scalar multiplication per side and a single
+    /// pairing check, and is true only if every proof is valid except with
+    /// negligible probability.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices have different lengths.
+    pub fn verify_batch(
+        &self,
+        commitments: &[G1Affine],
+        points: &[Scalar],
+        values: &[Scalar],
+        proofs: &[G1Affine],
+        mut rng: impl RngCore,
+    ) -> bool {
+        let weights = (0..commitments.len())
+            .map(|_| Scalar::random(&mut rng))
+            .collect::<Vec<_>>();
+        self.verify_batch_with_weights(commitments, points, values, proofs, &weights)
+    }
+
+    /// The check of [`verify_batch`](Self::verify_batch) with the given
+    /// weights, which must be unpredictable to whoever created the proofs.
+    pub(crate) fn verify_batch_with_weights(
+        &self,
+        commitments: &[G1Affine],
+        points: &[Scalar],
+        values: &[Scalar],
+        proofs: &[G1Affine],
+        weights: &[Scalar],
+    ) -> bool {
+        let n = commitments.len();
+        assert!(
+            points.len() == n && values.len() == n && proofs.len() == n && weights.len() == n,
+            "the number of commitments, points, values, proofs and weights differ"
+        );
+
+        // sum r_i (C_i - y_i G1 + z_i π_i) paired with G2, and
+        // sum r_i π_i paired with τ G2
+        let mut lhs_points = Vec::with_capacity(2 * n + 1);
+        let mut lhs_scalars = Vec::with_capacity(2 * n + 1);
+        let mut value = Scalar::ZERO;
+        for i in 0..n {
+            lhs_points.push(G1Projective::from(commitments[i]));
+            lhs_scalars.push(weights[i]);
+            lhs_points.push(G1Projective::from(proofs[i]));
+            lhs_scalars.push(weights[i] * points[i]);
+            value += weights[i] * values[i];
+        }
+        lhs_points.push(G1Projective::GENERATOR);
+        lhs_scalars.push(-value);
+
+        let proofs = proofs.iter().map(G1Projective::from).collect::<Vec<_>>();
+        let lhs = G1Affine::from(G1Projective::sum_of_products(&lhs_points, &lhs_scalars));
+        let rhs = G1Affine::from(G1Projective::sum_of_products(&proofs, weights));
+        self.check(&lhs, &rhs)
+    }
+
+    /// Checks that e(`lhs`, G2) = e(`rhs`, τ G2).
+    fn check(&self, lhs: &G1Affine, rhs: &G1Affine) -> bool {
+        multi_miller_loop(&[(lhs, &self.g2_prepared), (&-rhs, &self.tau_g2_prepared)])
+            .final_exponentiation()
+            .is_identity()
+            .into()
+    }
+
+    /// Returns the Lagrange basis domain if it has the size of `evaluations`.
+    fn domain_of(&self, evaluations: &[Scalar]) -> Option<&EvaluationDomain> {
+        self.domain
+            .as_ref()
+            .filter(|d| d.size() == evaluations.len())
+    }
+
+    /// Returns the Lagrange basis domain, which must hold `len` coefficients.
+    fn lagrange_domain(&self, len: usize) -> &EvaluationDomain {
+        self.domain
+            .as_ref()
+            .filter(|d| d.size() >= len)
+            .expect("the degree of the polynomial is too large for the setup")
+    }
+
+    /// Commits to evaluations over the Lagrange basis domain.
+    fn commit_lagrange(&self, evaluations: &[Scalar]) -> G1Affine {
+        G1Affine::from(G1Projective::sum_of_products(
+            &self.g1_lagrange,
+            evaluations,
+        ))
+    }
+
+    /// Interpolates evaluations over the domain of their length.
+    fn interpolate(evaluations: &[Scalar]) -> Polynomial {
+        let domain = EvaluationDomain::new(evaluations.len())
+            .filter(|d| d.size() == evaluations.len())
+            .expect("the number of evaluations must be a power of two");
+        Polynomial::interpolate_over_domain(&domain, evaluations)
+    }
+}
+
+/// Returns the polynomial prod (X - x) over `points`.
+fn vanishing_polynomial(points: &[Scalar]) -> Polynomial {
+    points.iter().fold(
+        Polynomial::from_coefficients(vec![Scalar::ONE]),
+        |acc, x| &acc * &Polynomial::from_coefficients(vec![-x, Scalar::ONE]),
+    )
+}
+
+#[test]
+fn test_open_verify() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let srs = Srs::insecure_from_secret(&Scalar::random(&mut rng), 8, 2);
+    let p = Polynomial::random(7, &mut rng);
+    let z = Scalar::random(&mut rng);
+
+    let commitment = srs.commit(&p);
+    let (proof, value) = srs.open(&p, &z);
+    assert_eq!(value, p.evaluate(&z));
+    assert!(srs.verify(&commitment, &z, &value, &proof));
+    assert!(!srs.verify(&commitment, &z, &(value + Scalar::ONE), &proof));
+    assert!(!srs.verify(&commitment, &(z + Scalar::ONE), &value, &proof));
+    assert!(!srs.verify(&G1Affine::generator(), &z, &value, &proof));
+
+    // Constant polynomials have an identity proof
+    let c = Polynomial::from_coefficients(vec![value]);
+    let (proof, _) = srs.open(&c, &z);
+    assert_eq!(proof, G1Affine::identity());
+    assert!(srs.verify(&srs.commit(&c), &z, &value, &proof));
+}
+
+#[test]
+fn test_lagrange_basis() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let tau = Scalar::random(&mut rng);
+    let monomial = Srs::insecure_from_secret(&tau, 8, 2);
+    let both = monomial.clone().with_lagrange_basis(3);
+    let domain = both.domain().unwrap().clone();
+    let lagrange = Srs::new(
+        Basis::Lagrange,
+        &both
+            .g1_lagrange
+            .iter()
+            .map(G1Affine::from)
+            .collect::<Vec<_>>(),
+        &[
+            G2Affine::generator(),
+            G2Affine::from(G2Projective::GENERATOR * tau),
+        ],
+    );
+    assert_eq!(lagrange.monomial_len(), 0);
+
+    let p = Polynomial::random(7, &mut rng);
+    let evaluations = p.evaluate_over_domain(&domain);
+    let commitment = monomial.commit(&p);
+    for srs in [&monomial, &both, &lagrange] {
+        assert_eq!(srs.commit(&p), commitment);
+        assert_eq!(srs.commit_evaluations(&evaluations), commitment);
+    }
+
+    // Outside and inside the domain
+    for z in [Scalar::random(&mut rng), domain.element(3), Scalar::ONE] {
+        let (proof, value) = monomial.open(&p, &z);
+        for srs in [&both, &lagrange] {
+            assert_eq!(srs.open_evaluations(&evaluations, &z), (proof, value));
+        }
+        assert_eq!(monomial.open_evaluations(&evaluations, &z), (proof, value));
+        assert!(lagrange.verify(&commitment, &z, &value, &proof));
+    }
+}
+
+#[test]
+fn test_open_multi() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let srs = Srs::insecure_from_secret(&Scalar::random(&mut rng), 8, 4);
+    let p = Polynomial::random(7, &mut rng);
+    let commitment = srs.commit(&p);
+
+    let points = (0..3).map(|_| Scalar::random(&mut rng)).collect::<Vec<_>>();
+    let (proof, values) = srs.open_multi(&p, &points).unwrap();
+    assert_eq!(values, p.evaluate_many(&points));
+    assert!(srs.verify_multi(&commitment, &points, &values, &proof));
+
+    let mut wrong = values.clone();
+    wrong[1] += Scalar::ONE;
+    assert!(!srs.verify_multi(&commitment, &points, &wrong, &proof));
+    assert!(srs.open_multi(&p, &[points[0], points[0]]).is_none());
+
+    // A single point agrees with the single point proof
+    let (single, value) = srs.open(&p, &points[0]);
+    assert_eq!(
+        srs.open_multi(&p, &points[..1]),
+        Some((single, vec![value]))
+    );
+
+    // Too many points for the G2 points
+    let points = (0..4).map(|_| Scalar::random(&mut rng)).collect::<Vec<_>>();
+    let (proof, values) = srs.open_multi(&p, &points).unwrap();
+    assert!(!srs.verify_multi(&commitment, &points, &values, &proof));
+}
+
+#[test]
+fn test_verify_batch() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let srs = Srs::insecure_from_secret(&Scalar::random(&mut rng), 4, 2);
+
+    let mut commitments = Vec::new();
+    let mut points = Vec::new();
+    let mut values = Vec::new();
+    let mut proofs = Vec::new();
+    for _ in 0..3 {
+        let p = Polynomial::random(3, &mut rng);
+        let z = Scalar::random(&mut rng);
+        let (proof, value) = srs.open(&p, &z);
+        commitments.push(srs.commit(&p));
+        points.push(z);
+        values.push(value);
+        proofs.push(proof);
+    }
+    assert!(srs.verify_batch(&commitments, &points, &values, &proofs, &mut rng));
+    assert!(srs.verify_batch(&[], &[], &[], &[], &mut rng));
+
+    values[2] += Scalar::ONE;
+    assert!(!srs.verify_batch(&commitments, &points, &values, &proofs, &mut rng));
+}
+
+#[test]
+fn test_from_compressed() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let tau = Scalar::random(&mut rng);
+    let srs = Srs::insecure_from_secret(&tau, 4, 2);
+    let g1 = srs
+        .g1_monomial
+        .iter()
+        .flat_map(|p| G1Affine::from(p).to_compressed())
+        .collect::<Vec<_>>();
+    let g2 = srs
+        .g2_monomial
+        .iter()
+        .flat_map(|p| G2Affine::from(p).to_compressed())
+        .collect::<Vec<_>>();
+
+    let decoded = Srs::from_compressed(Basis::Monomial, &g1, &g2).unwrap();
+    assert_eq!(decoded.g1_monomial, srs.g1_monomial);
+    assert_eq!(decoded.g2_monomial, srs.g2_monomial);
+
+    assert_eq!(
+        Srs::from_compressed(Basis::Lagrange, &g1[..144], &g2).unwrap_err(),
+        DecodingError::InvalidLength {
+            expected: 192,
+            found: 144
+        }
+    );
+    assert_eq!(
+        Srs::from_compressed(Basis::Monomial, &g1, &g2[..96]).unwrap_err(),
+        DecodingError::InvalidLength {
+            expected: 192,
+            found: 96
+        }
+    );
+    assert_eq!(
+        Srs::from_compressed(Basis::Monomial, &g1[..47], &g2).unwrap_err(),
+        DecodingError::InvalidLength {
+            expected: 48,
+            found: 47
+        }
+    );
+
+    let mut invalid = g1.clone();
+    invalid[48] ^= 0x80;
+    assert_eq!(
+        Srs::from_compressed(Basis::Monomial, &invalid, &g2).unwrap_err(),
+        DecodingError::InvalidFlags
+    );
+}
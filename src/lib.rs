@@ -42,6 +42,15 @@ mod tests;
 #[macro_use]
 mod util;
 
+mod limb32;
+mod safegcd;
+
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+mod asm;
+
+mod error;
+pub use error::DecodingError;
+
 /// Notes about how the BLS12-381 elliptic curve is designed, specified
 /// and implemented by this library.
 pub mod notes {
@@ -55,6 +64,16 @@ mod scalar;
 pub use scalar::run_test_wasm;
 pub use scalar::{Scalar, ScalarLe};
 
+#[cfg(feature = "alloc")]
+pub mod domain;
+#[cfg(feature = "alloc")]
+pub use domain::EvaluationDomain;
+
+#[cfg(feature = "alloc")]
+pub mod polynomial;
+#[cfg(feature = "alloc")]
+pub use polynomial::Polynomial;
+
 #[cfg(all(feature = "groups", not(feature = "expose-fields")))]
 mod fp;
 #[cfg(feature = "expose-fields")]
@@ -68,6 +87,11 @@ mod g1;
 #[cfg(feature = "groups")]
 mod g2;
 
+#[cfg(feature = "groups")]
+mod serde_adapters;
+#[cfg(feature = "groups")]
+pub use serde_adapters::{Base64, Unchecked, Uncompressed};
+
 #[cfg(feature = "groups")]
 pub use g1::{G1Affine, G1Projective};
 #[cfg(feature = "expose-fields")]
@@ -77,8 +101,14 @@ pub use g2::{G2Affine, G2Projective};
 #[cfg(feature = "expose-fields")]
 pub use g2::{G2Compressed, G2Uncompressed};
 
+#[cfg(not(feature = "expose-fields"))]
 mod fp12;
+#[cfg(feature = "expose-fields")]
+pub mod fp12;
+#[cfg(not(feature = "expose-fields"))]
 mod fp6;
+#[cfg(feature = "expose-fields")]
+pub mod fp6;
 
 use elliptic_curve::{
     bigint::{ArrayEncoding, U384},
@@ -88,6 +118,16 @@ use elliptic_curve::{
 };
 
 /// An engine for operations generic G1 operations
+///
+/// SEC1 encodings of [`G1Affine`] and [`G1Projective`] are provided through
+/// [`ToEncodedPoint`](elliptic_curve::sec1::ToEncodedPoint) and
+/// [`FromEncodedPoint`](elliptic_curve::sec1::FromEncodedPoint).
+///
+/// [`CurveArithmetic`](elliptic_curve::CurveArithmetic) is deliberately not
+/// implemented, so `PublicKey<Bls12381G1>` and `SecretKey<Bls12381G1>` are not
+/// available. It requires `PrimeField::Repr` of the scalar to be
+/// `FieldBytes<Self>`, which holds a 48-byte base field element, and changing
+/// the 32-byte repr of [`Scalar`] would break every user of `ff`.
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Bls12381G1;
 
@@ -95,6 +135,12 @@ unsafe impl Send for Bls12381G1 {}
 unsafe impl Sync for Bls12381G1 {}
 
 /// An engine for operations generic G2 operations
+///
+/// Neither SEC1 encodings nor
+/// [`CurveArithmetic`](elliptic_curve::CurveArithmetic) are implemented, and
+/// they cannot be: SEC1 in `elliptic-curve` supports coordinates of at most 66
+/// bytes, while those of $\mathbb{G}_2$ are 96 bytes, and `CurveArithmetic`
+/// requires SEC1 encodings of the affine points.
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Bls12381G2;
 
@@ -148,6 +194,13 @@ const BLS_X: u64 = 0xd201_0000_0001_0000;
 #[cfg(feature = "groups")]
 const BLS_X_IS_NEGATIVE: bool = true;
 
+#[cfg(feature = "groups")]
+pub mod arkworks;
+#[cfg(feature = "groups")]
+pub mod eip2537;
+#[cfg(all(feature = "groups", feature = "std"))]
+pub mod point_table;
+
 #[cfg(feature = "pairings")]
 mod pairings;
 
@@ -155,6 +208,11 @@ mod pairings;
 pub use pairings::{pairing, Bls12, Gt, MillerLoopResult};
 
 #[cfg(feature = "pairings")]
-pub use pairings::{multi_miller_loop, G2Prepared};
+pub use pairings::{multi_miller_loop, multi_miller_loop_prepared, G1Prepared, G2Prepared};
+
+#[cfg(all(feature = "pairings", feature = "alloc"))]
+pub mod eip4844;
+#[cfg(all(feature = "pairings", feature = "alloc"))]
+pub mod kzg;
 
 mod isogeny;
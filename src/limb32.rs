@@ -0,0 +1,224 @@
+//! Montgomery multiplication with 32-bit limbs, for targets such as wasm32
+//! and Cortex-M where the `u128` products of `mac` are emulated in software.
+//!
+//! Field elements keep their `u64` limbs, which are split into `u32` limbs
+//! around each multiplication, so encodings are shared with the 64-bit code.
+//! The remaining arithmetic goes through `adc`, `sbb` and `mac` in `util`,
+//! which switch to the versions here that avoid `u128` entirely. This
+//! backend is used on targets with 32-bit pointers and wherever the
+//! `u32-backend` feature is enabled.
+
+/// Whether arithmetic uses this backend.
+pub(crate) const ENABLED: bool = cfg!(any(feature = "u32-backend", target_pointer_width = "32"));
+
+/// Compute a + b + carry, returning the result and the new carry over, like
+/// `util::adc` but without `u128` arithmetic.
+#[inline(always)]
+pub(crate) const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let (sum, c0) = a.overflowing_add(b);
+    let (sum, c1) = sum.overflowing_add(carry);
+    (sum, c0 as u64 + c1 as u64)
+}
+
+/// Compute a - (b + borrow), returning the result and the new borrow, like
+/// `util::sbb` but without `u128` arithmetic.
+#[inline(always)]
+pub(crate) const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let (diff, b0) = a.overflowing_sub(b);
+    // a - b cannot wrap to zero, so at most one of the subtractions borrows
+    let (diff, b1) = diff.overflowing_sub(borrow >> 63);
+    (diff, 0u64.wrapping_sub((b0 | b1) as u64))
+}
+
+/// Compute a + (b * c) + carry, returning the result and the new carry over,
+/// like `util::mac` but from the 32-bit halves of `b` and `c`, whose products
+/// are native on 32-bit targets.
+#[inline(always)]
+pub(crate) const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let (b0, b1) = (b & 0xffff_ffff, b >> 32);
+    let (c0, c1) = (c & 0xffff_ffff, c >> 32);
+    let (lo, cross0, cross1, hi) = (b0 * c0, b1 * c0, b0 * c1, b1 * c1);
+
+    // The middle column is less than 3 * 2^32
+    let mid = (lo >> 32) + (cross0 & 0xffff_ffff) + (cross1 & 0xffff_ffff);
+    let lo = (lo & 0xffff_ffff) | (mid << 32);
+    let hi = hi + (cross0 >> 32) + (cross1 >> 32) + (mid >> 32);
+
+    // The whole sum is less than 2^128, so the high limb cannot overflow
+    let (lo, c0) = lo.overflowing_add(a);
+    let (lo, c1) = lo.overflowing_add(carry);
+    (lo, hi + c0 as u64 + c1 as u64)
+}
+
+/// Compute a + (b * c) + carry, returning the result and the new carry over.
+#[inline(always)]
+const fn mac32(a: u32, b: u32, c: u32, carry: u32) -> (u32, u32) {
+    let ret = (a as u64) + ((b as u64) * (c as u64)) + (carry as u64);
+    (ret as u32, (ret >> 32) as u32)
+}
+
+/// Splits little-endian `u64` limbs into twice as many `u32` limbs.
+#[inline(always)]
+pub(crate) const fn to_u32<const N: usize, const M: usize>(a: &[u64; N]) -> [u32; M] {
+    let mut out = [0u32; M];
+    let mut i = 0;
+    while i < N {
+        out[2 * i] = a[i] as u32;
+        out[2 * i + 1] = (a[i] >> 32) as u32;
+        i += 1;
+    }
+    out
+}
+
+/// Joins little-endian `u32` limbs into half as many `u64` limbs.
+#[inline(always)]
+pub(crate) const fn to_u64<const M: usize, const N: usize>(a: &[u32; M]) -> [u64; N] {
+    let mut out = [0u64; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = a[2 * i] as u64 | ((a[2 * i + 1] as u64) << 32);
+        i += 1;
+    }
+    out
+}
+
+/// Returns `sum(a_i * b_i) / R mod m` with `R = 2^(32 * N)`, where `inv` is
+/// `-m^-1 mod 2^32`. The result is less than `2m` and needs a final
+/// conditional subtraction.
+///
+/// This is Algorithm 2 of Patrick Longa's
+/// [ePrint 2022-367](https://eprint.iacr.org/2022/367) §3, as used by
+/// `Fp::sum_of_products`, with the same requirements: every `b_i` must be
+/// reduced, while the `a_i` may be any `N`-limb value, and the modulus must
+/// leave enough spare bits in its top limb for `T` products.
+#[inline(always)]
+pub(crate) const fn sum_of_products<const N: usize, const T: usize>(
+    a: &[[u32; N]; T],
+    b: &[[u32; N]; T],
+    m: &[u32; N],
+    inv: u32,
+) -> [u32; N] {
+    let mut u = [0u32; N];
+    let mut j = 0;
+    while j < N {
+        // Accumulate the jth limb of each a_i times b_i, with one extra limb
+        let mut top = 0u32;
+        let mut i = 0;
+        while i < T {
+            let mut carry = 0;
+            let mut k = 0;
+            while k < N {
+                (u[k], carry) = mac32(u[k], a[i][j], b[i][k], carry);
+                k += 1;
+            }
+            top = top.wrapping_add(carry);
+            i += 1;
+        }
+
+        // A single step of Montgomery reduction, shifting down by one limb
+        let q = u[0].wrapping_mul(inv);
+        let (_, mut carry) = mac32(u[0], q, m[0], 0);
+        let mut k = 1;
+        while k < N {
+            (u[k - 1], carry) = mac32(u[k], q, m[k], carry);
+            k += 1;
+        }
+        u[N - 1] = top.wrapping_add(carry);
+        j += 1;
+    }
+    u
+}
+
+/// Returns the double-width product of `a` and `b`.
+#[inline(always)]
+pub(crate) const fn mul_wide<const N: usize, const W: usize>(
+    a: &[u32; N],
+    b: &[u32; N],
+) -> [u32; W] {
+    let mut t = [0u32; W];
+    let mut i = 0;
+    while i < N {
+        let mut carry = 0;
+        let mut j = 0;
+        while j < N {
+            (t[i + j], carry) = mac32(t[i + j], a[i], b[j], carry);
+            j += 1;
+        }
+        t[i + N] = carry;
+        i += 1;
+    }
+    t
+}
+
+/// Returns `t / R mod m` with `R = 2^(32 * N)`, where `inv` is
+/// `-m^-1 mod 2^32`. For `t < m * R` the result is less than `2m` and needs a
+/// final conditional subtraction.
+#[inline(always)]
+pub(crate) const fn montgomery_reduce<const W: usize, const N: usize>(
+    t: &[u32; W],
+    m: &[u32; N],
+    inv: u32,
+) -> [u32; N] {
+    let mut t = *t;
+    // The carry out of the limb above each row, added to the next row
+    let mut carry2 = 0u32;
+    let mut i = 0;
+    while i < N {
+        let q = t[i].wrapping_mul(inv);
+        let mut carry = 0;
+        let mut j = 0;
+        while j < N {
+            (t[i + j], carry) = mac32(t[i + j], q, m[j], carry);
+            j += 1;
+        }
+        let sum = t[i + N] as u64 + carry as u64 + carry2 as u64;
+        t[i + N] = sum as u32;
+        carry2 = (sum >> 32) as u32;
+        i += 1;
+    }
+
+    let mut out = [0u32; N];
+    let mut j = 0;
+    while j < N {
+        out[j] = t[N + j];
+        j += 1;
+    }
+    out
+}
+
+#[test]
+fn test_carry_arithmetic() {
+    use rand_core::{RngCore, SeedableRng};
+
+    // Compare against the u128 definitions, on the extremes and at random
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let mut values = [
+        0,
+        1,
+        2,
+        0xffff_ffff,
+        1 << 32,
+        1 << 63,
+        u64::MAX - 1,
+        u64::MAX,
+    ]
+    .to_vec();
+    values.extend((0..24).map(|_| rng.next_u64()));
+    for &a in &values {
+        for &b in &values {
+            for &c in &values {
+                let sum = a as u128 + b as u128 + c as u128;
+                assert_eq!(adc(a, b, c), (sum as u64, (sum >> 64) as u64));
+                let product = a as u128 + b as u128 * c as u128 + (c >> 1) as u128;
+                assert_eq!(
+                    mac(a, b, c, c >> 1),
+                    (product as u64, (product >> 64) as u64)
+                );
+            }
+            for borrow in [0, u64::MAX] {
+                let diff = (a as u128).wrapping_sub(b as u128 + (borrow >> 63) as u128);
+                assert_eq!(sbb(a, b, borrow), (diff as u64, (diff >> 64) as u64));
+            }
+        }
+    }
+}
@@ -1,11 +1,12 @@
+use crate::arkworks::{reverse_fp_chunks, Validate};
 use crate::fp::Fp;
 use crate::fp12::Fp12;
 use crate::fp2::Fp2;
 use crate::fp6::Fp6;
 use crate::util::decode_hex_byte;
+use crate::DecodingError;
 use crate::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar, BLS_X, BLS_X_IS_NEGATIVE};
 
-use arrayref::array_ref;
 use core::{
     borrow::Borrow,
     fmt::{self, Display, Formatter, LowerHex, UpperHex},
@@ -41,6 +42,50 @@ impl ConditionallySelectable for MillerLoopResult {
 }
 
 impl MillerLoopResult {
+    /// The multiplicative identity, which is the output of a Miller loop
+    /// with no terms.
+    pub const IDENTITY: Self = Self(Fp12::ONE);
+
+    /// Bytes to represent this value
+    pub const BYTES: usize = 576;
+
+    const HEX_BYTES: usize = Self::BYTES * 2;
+
+    /// Returns the underlying element of $\mathbb{F}_{p^{12}}$.
+    #[cfg(feature = "expose-fields")]
+    pub fn as_fp12(&self) -> &Fp12 {
+        &self.0
+    }
+
+    /// Return the byte representation of this value in big-endian
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        self.0.to_bytes()
+    }
+
+    /// Attempts to convert a big-endian byte representation into a
+    /// `MillerLoopResult`, failing if the input is not canonical or is zero.
+    pub fn from_bytes(bytes: &[u8; Self::BYTES]) -> CtOption<Self> {
+        Fp12::from_bytes(bytes).and_then(|f| CtOption::new(Self(f), !f.is_zero()))
+    }
+
+    /// Multiplies two Miller loop results together. This is the same as
+    /// adding them and allows results computed separately, e.g. on
+    /// different machines, to be combined before a single final exponentiation.
+    pub fn product(a: &Self, b: &Self) -> Self {
+        a + b
+    }
+
+    /// Compute the inverse of this element in $\mathbb{F}_{p^{12}}$.
+    pub fn invert(&self) -> CtOption<Self> {
+        self.0.invert().map(Self)
+    }
+
+    /// Compute the conjugate of this element. After the final exponentiation
+    /// this is the inverse, and is much cheaper than [`invert`](Self::invert).
+    pub fn conjugate(&self) -> Self {
+        -self
+    }
+
     /// This performs a "final exponentiation" routine to convert the result
     /// of a Miller loop into an element of `Gt` with help of efficient squaring
     /// operation in the so-called `cyclotomic subgroup` of `Fq6` so that
@@ -48,16 +93,15 @@ impl MillerLoopResult {
     pub fn final_exponentiation(&self) -> Gt {
         #[must_use]
         fn fp4_square(a: Fp2, b: Fp2) -> (Fp2, Fp2) {
-            let t0 = a.square();
-            let t1 = b.square();
+            // The squares are kept unreduced until both outputs are formed
+            let t0 = a.square_wide();
+            let t1 = b.square_wide();
             let mut t2 = t1.mul_by_nonresidue();
             let c0 = t2 + t0;
-            t2 = a + b;
-            t2 = t2.square();
-            t2 -= t0;
-            let c1 = t2 - t1;
+            t2 = (a + b).square_wide();
+            let c1 = t2 - t0 - t1;
 
-            (c0, c1)
+            (c0.reduce(), c1.reduce())
         }
         // Adaptation of Algorithm 5.5.4, Guide to Pairing-Based Cryptography
         // Faster Squaring in the Cyclotomic Subgroup of Sixth Degree Extensions
@@ -185,22 +229,98 @@ impl<'a, 'b> Add<&'b MillerLoopResult> for &'a MillerLoopResult {
     }
 }
 
-impl_add_binop_specify_output!(MillerLoopResult, MillerLoopResult, MillerLoopResult);
+impl<'a> Neg for &'a MillerLoopResult {
+    type Output = MillerLoopResult;
+
+    #[inline]
+    fn neg(self) -> MillerLoopResult {
+        // Conjugation commutes with the final exponentiation, whose output
+        // is unitary, so this is the inverse once in $\mathbb{G}_T$.
+        MillerLoopResult(self.0.conjugate())
+    }
+}
+
+impl Neg for MillerLoopResult {
+    type Output = MillerLoopResult;
 
-impl AddAssign<MillerLoopResult> for MillerLoopResult {
     #[inline]
-    fn add_assign(&mut self, rhs: MillerLoopResult) {
-        *self = *self + rhs;
+    fn neg(self) -> MillerLoopResult {
+        -&self
     }
 }
 
-impl<'b> AddAssign<&'b MillerLoopResult> for MillerLoopResult {
+impl<'a, 'b> Sub<&'b MillerLoopResult> for &'a MillerLoopResult {
+    type Output = MillerLoopResult;
+
     #[inline]
-    fn add_assign(&mut self, rhs: &'b MillerLoopResult) {
-        *self = *self + rhs;
+    fn sub(self, rhs: &'b MillerLoopResult) -> MillerLoopResult {
+        self + (-rhs)
+    }
+}
+
+impl<'a, 'b> Mul<&'b Scalar> for &'a MillerLoopResult {
+    type Output = MillerLoopResult;
+
+    fn mul(self, other: &'b Scalar) -> Self::Output {
+        let mut acc = MillerLoopResult::IDENTITY;
+
+        // This is a simple square-and-multiply implementation, moving from
+        // most significant to least significant bit of the scalar.
+        //
+        // We skip the leading bit because it's always unset for Fq
+        // elements.
+        for bit in other
+            .to_le_bytes()
+            .iter()
+            .rev()
+            .flat_map(|byte| (0..8).rev().map(move |i| Choice::from((byte >> i) & 1u8)))
+            .skip(1)
+        {
+            acc = MillerLoopResult(acc.0.square());
+            acc = MillerLoopResult::conditional_select(&acc, &(acc + self), bit);
+        }
+
+        acc
+    }
+}
+
+impl_binops_additive!(MillerLoopResult, MillerLoopResult);
+impl_binops_multiplicative!(MillerLoopResult, Scalar);
+
+impl<T> Sum<T> for MillerLoopResult
+where
+    T: Borrow<MillerLoopResult>,
+{
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        iter.fold(Self::IDENTITY, |acc, item| acc + item.borrow())
     }
 }
 
+impl_serde!(
+    MillerLoopResult,
+    |p: &MillerLoopResult| p.to_bytes(),
+    |arr: &[u8; MillerLoopResult::BYTES]| MillerLoopResult::from_bytes(arr),
+    MillerLoopResult::BYTES,
+    MillerLoopResult::HEX_BYTES
+);
+
+impl_from_bytes!(
+    MillerLoopResult,
+    |p: &MillerLoopResult| p.to_bytes(),
+    |arr: &[u8]| -> Result<MillerLoopResult, DecodingError> {
+        let bytes = DecodingError::to_array(arr)?;
+        let f = Option::<Fp12>::from(Fp12::from_bytes(&bytes))
+            .ok_or(DecodingError::NonCanonicalField)?;
+        if bool::from(f.is_zero()) {
+            return Err(DecodingError::Zero);
+        }
+        Ok(MillerLoopResult(f))
+    }
+);
+
 /// This is an element of $\mathbb{G}_T$, the target group of the pairing function. As with
 /// $\mathbb{G}_1$ and $\mathbb{G}_2$ this group has order $q$.
 ///
@@ -284,97 +404,47 @@ impl Gt {
         Gt(self.0.square())
     }
 
+    /// Returns the underlying element of $\mathbb{F}_{p^{12}}$.
+    #[cfg(feature = "expose-fields")]
+    pub fn as_fp12(&self) -> &Fp12 {
+        &self.0
+    }
+
     /// Return the byte representation of this value in big-endian
     pub fn to_bytes(&self) -> [u8; Self::BYTES] {
-        let mut output = [0u8; Self::BYTES];
-        output[..48].copy_from_slice(&self.0.c0.c0.c0.to_bytes());
-        output[48..96].copy_from_slice(&self.0.c0.c0.c1.to_bytes());
-        output[96..144].copy_from_slice(&self.0.c0.c1.c0.to_bytes());
-        output[144..192].copy_from_slice(&self.0.c0.c1.c1.to_bytes());
-        output[192..240].copy_from_slice(&self.0.c0.c2.c0.to_bytes());
-        output[240..288].copy_from_slice(&self.0.c0.c2.c1.to_bytes());
-        output[288..336].copy_from_slice(&self.0.c1.c0.c0.to_bytes());
-        output[336..384].copy_from_slice(&self.0.c1.c0.c1.to_bytes());
-        output[384..432].copy_from_slice(&self.0.c1.c1.c0.to_bytes());
-        output[432..480].copy_from_slice(&self.0.c1.c1.c1.to_bytes());
-        output[480..528].copy_from_slice(&self.0.c1.c2.c0.to_bytes());
-        output[528..Self::BYTES].copy_from_slice(&self.0.c1.c2.c1.to_bytes());
-        output
+        self.0.to_bytes()
     }
 
     /// Attempts to convert a big-endian byte representation of
     /// a scalar into a `Gt`, failing if the input is not canonical.
     pub fn from_bytes(bytes: &[u8; Self::BYTES]) -> CtOption<Self> {
-        let c000 = Fp::from_bytes(array_ref![bytes, 0, 48]);
-        let c001 = Fp::from_bytes(array_ref![bytes, 48, 48]);
-        let c010 = Fp::from_bytes(array_ref![bytes, 96, 48]);
-        let c011 = Fp::from_bytes(array_ref![bytes, 144, 48]);
-        let c020 = Fp::from_bytes(array_ref![bytes, 192, 48]);
-        let c021 = Fp::from_bytes(array_ref![bytes, 240, 48]);
-        let c100 = Fp::from_bytes(array_ref![bytes, 288, 48]);
-        let c101 = Fp::from_bytes(array_ref![bytes, 336, 48]);
-        let c110 = Fp::from_bytes(array_ref![bytes, 384, 48]);
-        let c111 = Fp::from_bytes(array_ref![bytes, 432, 48]);
-        let c120 = Fp::from_bytes(array_ref![bytes, 480, 48]);
-        let c121 = Fp::from_bytes(array_ref![bytes, 528, 48]);
-
-        c000.and_then(|cc000| {
-            c001.and_then(|cc001| {
-                c010.and_then(|cc010| {
-                    c011.and_then(|cc011| {
-                        c020.and_then(|cc020| {
-                            c021.and_then(|cc021| {
-                                c100.and_then(|cc100| {
-                                    c101.and_then(|cc101| {
-                                        c110.and_then(|cc110| {
-                                            c111.and_then(|cc111| {
-                                                c120.and_then(|cc120| {
-                                                    c121.and_then(|cc121| {
-                                                        CtOption::new(
-                                                            Gt(Fp12 {
-                                                                c0: Fp6 {
-                                                                    c0: Fp2 {
-                                                                        c0: cc000,
-                                                                        c1: cc001,
-                                                                    },
-                                                                    c1: Fp2 {
-                                                                        c0: cc010,
-                                                                        c1: cc011,
-                                                                    },
-                                                                    c2: Fp2 {
-                                                                        c0: cc020,
-                                                                        c1: cc021,
-                                                                    },
-                                                                },
-                                                                c1: Fp6 {
-                                                                    c0: Fp2 {
-                                                                        c0: cc100,
-                                                                        c1: cc101,
-                                                                    },
-                                                                    c1: Fp2 {
-                                                                        c0: cc110,
-                                                                        c1: cc111,
-                                                                    },
-                                                                    c2: Fp2 {
-                                                                        c0: cc120,
-                                                                        c1: cc121,
-                                                                    },
-                                                                },
-                                                            }),
-                                                            Choice::from(1u8),
-                                                        )
-                                                    })
-                                                })
-                                            })
-                                        })
-                                    })
-                                })
-                            })
-                        })
-                    })
-                })
-            })
-        })
+        Fp12::from_bytes(bytes).map(Gt)
+    }
+
+    /// Return the byte representation of this value using the little-endian
+    /// encoding described in [`arkworks`](crate::arkworks).
+    pub fn to_bytes_le(&self) -> [u8; Self::BYTES] {
+        let mut bytes = self.to_bytes();
+        reverse_fp_chunks(&mut bytes);
+        bytes
+    }
+
+    /// Attempts to convert a little-endian byte representation described in
+    /// [`arkworks`](crate::arkworks) into a `Gt`, failing if the input is not
+    /// canonical, or not in the prime order subgroup if `validate` is
+    /// [`Validate::Yes`].
+    pub fn from_bytes_le(bytes: &[u8; Self::BYTES], validate: Validate) -> CtOption<Self> {
+        let mut tmp = *bytes;
+        reverse_fp_chunks(&mut tmp);
+        Self::from_bytes(&tmp)
+            .and_then(|g| CtOption::new(g, !validate.choice() | g.is_torsion_free()))
+    }
+
+    /// Returns true if this element is in the prime order subgroup, that is if
+    /// multiplying it by the order $q$ gives the identity.
+    pub fn is_torsion_free(&self) -> Choice {
+        // Compute [q - 1] P + P since q itself is zero as a scalar
+        (self * -Scalar::ONE + self).ct_eq(&Self::IDENTITY) & !self.0.is_zero()
     }
 
     /// Attempts to convert a big-endian hex representation of
@@ -707,18 +777,59 @@ impl_serde!(
     Gt::HEX_BYTES
 );
 
-impl_from_bytes!(Gt, |p: &Gt| p.to_bytes(), |arr: &[u8]| {
-    if arr.len() != Gt::BYTES {
-        return Err(alloc::format!(
-            "Invalid number of bytes for Gt, expected {}, found {}",
-            Gt::BYTES,
-            arr.len()
-        ));
+impl_from_bytes!(
+    Gt,
+    |p: &Gt| p.to_bytes(),
+    |arr: &[u8]| -> Result<Gt, DecodingError> {
+        let bytes = DecodingError::to_array(arr)?;
+        Option::from(Gt::from_bytes(&bytes)).ok_or(DecodingError::NonCanonicalField)
+    }
+);
+
+#[cfg_attr(docsrs, doc(cfg(all(feature = "pairings"))))]
+#[derive(Copy, Clone, Debug)]
+/// This structure contains cached computations pertaining to a $\mathbb{G}_1$
+/// element as part of the pairing function. The line functions evaluated in
+/// the Miller loop only ever use $-2x$ and $2y$ of the $\mathbb{G}_1$ element,
+/// so these are computed once here rather than in every step. This should be
+/// used whenever a $\mathbb{G}_1$ element, such as a generator or a commitment,
+/// is paired against many $\mathbb{G}_2$ elements, in conjunction with the
+/// [`multi_miller_loop_prepared`](crate::multi_miller_loop_prepared) function
+/// provided by this crate.
+///
+/// Requires the `pairing` crate features to be enabled.
+pub struct G1Prepared {
+    infinity: Choice,
+    neg_two_x: Fp,
+    two_y: Fp,
+}
+
+impl From<G1Affine> for G1Prepared {
+    fn from(p: G1Affine) -> G1Prepared {
+        G1Prepared::from(&p)
     }
-    let mut buf = [0u8; Gt::BYTES];
-    buf.copy_from_slice(arr);
-    Ok(Gt::from_bytes(&buf))
-});
+}
+
+impl<'a> From<&'a G1Affine> for G1Prepared {
+    fn from(p: &'a G1Affine) -> G1Prepared {
+        let is_identity = p.is_identity();
+        let p = G1Affine::conditional_select(p, &G1Affine::generator(), is_identity);
+        let two_x = p.x + p.x;
+
+        G1Prepared {
+            infinity: is_identity,
+            neg_two_x: -two_x,
+            two_y: p.y + p.y,
+        }
+    }
+}
+
+impl G1Prepared {
+    /// Returns true if the prepared element is the identity.
+    pub fn is_identity(&self) -> Choice {
+        self.infinity
+    }
+}
 
 #[cfg_attr(docsrs, doc(cfg(all(feature = "pairings"))))]
 #[derive(Clone, Debug)]
@@ -785,37 +896,70 @@ impl From<G2Affine> for G2Prepared {
 ///
 /// Requires the `pairing` crate features to be enabled.
 pub fn multi_miller_loop(terms: &[(&G1Affine, &G2Prepared)]) -> MillerLoopResult {
-    struct Adder<'a, 'b, 'c> {
-        terms: &'c [(&'a G1Affine, &'b G2Prepared)],
+    // Preparing a G1 element costs a few additions, which is cheap enough to
+    // repeat on every step rather than allocate room for the prepared terms.
+    multi_miller_loop_with(|| terms.iter().map(|(p, q)| (G1Prepared::from(*p), *q)))
+}
+
+#[cfg_attr(docsrs, doc(cfg(all(feature = "pairings"))))]
+/// Computes $$\sum_{i=1}^n \textbf{ML}(a_i, b_i)$$ given a series of terms
+/// $$(a_1, b_1), (a_2, b_2), ..., (a_n, b_n)$$ where both sides have been
+/// prepared in advance. This avoids preparing the $\mathbb{G}_1$ elements on
+/// every step of the Miller loop, which matters when the same $\mathbb{G}_1$
+/// element appears in many pairings.
+///
+/// Requires the `pairing` crate features to be enabled.
+pub fn multi_miller_loop_prepared(terms: &[(&G1Prepared, &G2Prepared)]) -> MillerLoopResult {
+    multi_miller_loop_with(|| terms.iter().copied())
+}
+
+/// The Miller loop over the terms returned by `terms`, which is called once
+/// per step so that the $\mathbb{G}_1$ elements can be prepared on the fly.
+fn multi_miller_loop_with<'a, F, I, P>(terms: F) -> MillerLoopResult
+where
+    F: Fn() -> I,
+    I: Iterator<Item = (P, &'a G2Prepared)>,
+    P: Borrow<G1Prepared>,
+{
+    struct Adder<F> {
+        terms: F,
         index: usize,
     }
 
-    impl<'a, 'b, 'c> MillerLoopDriver for Adder<'a, 'b, 'c> {
-        type Output = Fp12;
-
-        fn doubling_step(&mut self, mut f: Self::Output) -> Self::Output {
+    impl<F> Adder<F> {
+        fn step<'a, I, P>(&mut self, mut f: Fp12) -> Fp12
+        where
+            F: Fn() -> I,
+            I: Iterator<Item = (P, &'a G2Prepared)>,
+            P: Borrow<G1Prepared>,
+        {
             let index = self.index;
-            for term in self.terms {
-                let either_identity = term.0.is_identity() | term.1.infinity;
+            for (p, q) in (self.terms)() {
+                let p = p.borrow();
+                let either_identity = p.infinity | q.infinity;
 
-                let new_f = ell(f, &term.1.coeffs[index], term.0);
+                let new_f = ell(f, &q.coeffs[index], p);
                 f = Fp12::conditional_select(&new_f, &f, either_identity);
             }
             self.index += 1;
 
             f
         }
-        fn addition_step(&mut self, mut f: Self::Output) -> Self::Output {
-            let index = self.index;
-            for term in self.terms {
-                let either_identity = term.0.is_identity() | term.1.infinity;
+    }
 
-                let new_f = ell(f, &term.1.coeffs[index], term.0);
-                f = Fp12::conditional_select(&new_f, &f, either_identity);
-            }
-            self.index += 1;
+    impl<'a, F, I, P> MillerLoopDriver for Adder<F>
+    where
+        F: Fn() -> I,
+        I: Iterator<Item = (P, &'a G2Prepared)>,
+        P: Borrow<G1Prepared>,
+    {
+        type Output = Fp12;
 
-            f
+        fn doubling_step(&mut self, f: Self::Output) -> Self::Output {
+            self.step(f)
+        }
+        fn addition_step(&mut self, f: Self::Output) -> Self::Output {
+            self.step(f)
         }
         fn square_output(f: Self::Output) -> Self::Output {
             f.square()
@@ -841,7 +985,7 @@ pub fn pairing(p: &G1Affine, q: &G2Affine) -> Gt {
     struct Adder {
         cur: G2Projective,
         base: G2Affine,
-        p: G1Affine,
+        p: G1Prepared,
     }
 
     impl MillerLoopDriver for Adder {
@@ -873,7 +1017,7 @@ pub fn pairing(p: &G1Affine, q: &G2Affine) -> Gt {
     let mut adder = Adder {
         cur: G2Projective::from(q),
         base: q,
-        p,
+        p: G1Prepared::from(p),
     };
 
     let tmp = miller_loop(&mut adder);
@@ -922,15 +1066,17 @@ fn miller_loop<D: MillerLoopDriver>(driver: &mut D) -> D::Output {
     f
 }
 
-fn ell(f: Fp12, coeffs: &(Fp2, Fp2, Fp2), p: &G1Affine) -> Fp12 {
+fn ell(f: Fp12, coeffs: &(Fp2, Fp2, Fp2), p: &G1Prepared) -> Fp12 {
     let mut c0 = coeffs.0;
     let mut c1 = coeffs.1;
 
-    c0.c0 *= p.y;
-    c0.c1 *= p.y;
+    // The doubling and negation of these coefficients is folded into the
+    // prepared coordinates of the G1 element.
+    c0.c0 *= p.two_y;
+    c0.c1 *= p.two_y;
 
-    c1.c0 *= p.x;
-    c1.c1 *= p.x;
+    c1.c0 *= p.neg_two_x;
+    c1.c1 *= p.neg_two_x;
 
     f.mul_by_014(&coeffs.2, &c1, &c0)
 }
@@ -954,15 +1100,14 @@ fn doubling_step(r: &mut G2Projective) -> (Fp2, Fp2, Fp2) {
     let tmp2 = tmp2 + tmp2;
     r.y -= tmp2;
     let tmp3 = tmp4 * zsquared;
-    let tmp3 = tmp3 + tmp3;
-    let tmp3 = -tmp3;
     let tmp6 = tmp6.square() - tmp0 - tmp5;
     let tmp1 = tmp1 + tmp1;
     let tmp1 = tmp1 + tmp1;
     let tmp6 = tmp6 - tmp1;
     let tmp0 = r.z * zsquared;
-    let tmp0 = tmp0 + tmp0;
 
+    // The first two coefficients are left undoubled, and the second is not
+    // negated; see `ell`.
     (tmp0, tmp3, tmp6)
 }
 
@@ -991,11 +1136,10 @@ fn addition_step(r: &mut G2Projective, q: &G2Affine) -> (Fp2, Fp2, Fp2) {
     let ztsquared = r.z.square();
     let t10 = t10 - ztsquared;
     let t9 = t9 + t9 - t10;
-    let t10 = r.z + r.z;
-    let t6 = -t6;
-    let t1 = t6 + t6;
 
-    (t10, t1, t9)
+    // The first two coefficients are left undoubled, and the second is not
+    // negated; see `ell`.
+    (r.z, t6, t9)
 }
 
 impl PairingCurveAffine for G1Affine {
@@ -1353,3 +1497,165 @@ fn test_product() {
 
     assert_eq!(product, product_2);
 }
+
+#[test]
+fn test_miller_loop_result_arithmetic() {
+    let a = G1Affine::from(G1Affine::generator() * Scalar::from(3u64));
+    let b = G2Prepared::from(G2Affine::generator());
+    let c = G1Affine::generator();
+    let d = G2Prepared::from(G2Affine::from(G2Affine::generator() * Scalar::from(5u64)));
+
+    let m1 = multi_miller_loop(&[(&a, &b)]);
+    let m2 = multi_miller_loop(&[(&c, &d)]);
+    let combined = multi_miller_loop(&[(&a, &b), (&c, &d)]);
+
+    assert_eq!(
+        (m1 + m2).final_exponentiation(),
+        combined.final_exponentiation()
+    );
+    assert_eq!(
+        MillerLoopResult::product(&m1, &m2).final_exponentiation(),
+        combined.final_exponentiation()
+    );
+    assert_eq!(
+        [m1, m2]
+            .iter()
+            .sum::<MillerLoopResult>()
+            .final_exponentiation(),
+        combined.final_exponentiation()
+    );
+    assert_eq!(
+        (combined - m2).final_exponentiation(),
+        m1.final_exponentiation()
+    );
+    assert_eq!((m1 + (-m1)).final_exponentiation(), Gt::IDENTITY);
+    assert_eq!(
+        (m1 + m1.invert().unwrap()).final_exponentiation(),
+        Gt::IDENTITY
+    );
+    assert_eq!(
+        (m1 * Scalar::from(7u64)).final_exponentiation(),
+        m1.final_exponentiation() * Scalar::from(7u64)
+    );
+}
+
+#[test]
+fn test_miller_loop_result_serialization() {
+    let a = G1Affine::from(G1Affine::generator() * Scalar::from(11u64));
+    let b = G2Prepared::from(G2Affine::generator());
+    let m = multi_miller_loop(&[(&a, &b)]);
+
+    let bytes = m.to_bytes();
+    let m2 = MillerLoopResult::from_bytes(&bytes).unwrap();
+    assert_eq!(m.final_exponentiation(), m2.final_exponentiation());
+
+    let vec = serde_bare::to_vec(&m).unwrap();
+    let m3: MillerLoopResult = serde_bare::from_slice(&vec).unwrap();
+    assert_eq!(m.0, m3.0);
+
+    assert!(bool::from(
+        MillerLoopResult::from_bytes(&[0u8; MillerLoopResult::BYTES]).is_none()
+    ));
+    assert!(bool::from(
+        MillerLoopResult::from_bytes(&[0xffu8; MillerLoopResult::BYTES]).is_none()
+    ));
+}
+
+#[test]
+fn test_multi_miller_loop_prepared() {
+    let a1 = G1Affine::generator();
+    let a2 = G1Affine::from(G1Affine::generator() * Scalar::from(1234u64));
+    let b1 = G2Affine::from(G2Affine::generator() * Scalar::from(77u64));
+    let b2 = G2Affine::generator();
+
+    let a1_prepared = G1Prepared::from(a1);
+    let a2_prepared = G1Prepared::from(&a2);
+    let identity_prepared = G1Prepared::from(G1Affine::identity());
+    let b1_prepared = G2Prepared::from(b1);
+    let b2_prepared = G2Prepared::from(b2);
+
+    assert!(bool::from(identity_prepared.is_identity()));
+    assert!(!bool::from(a1_prepared.is_identity()));
+
+    let expected = multi_miller_loop(&[(&a1, &b1_prepared), (&a2, &b2_prepared)]);
+    let actual = multi_miller_loop_prepared(&[
+        (&a1_prepared, &b1_prepared),
+        (&a2_prepared, &b2_prepared),
+        (&identity_prepared, &b1_prepared),
+    ]);
+    assert_eq!(expected.0, actual.0);
+
+    assert_eq!(
+        multi_miller_loop_prepared(&[(&a1_prepared, &b1_prepared)]).final_exponentiation(),
+        pairing(&a1, &b1)
+    );
+    assert_eq!(
+        multi_miller_loop_prepared(&[(&identity_prepared, &b2_prepared)]).final_exponentiation(),
+        Gt::IDENTITY
+    );
+}
+
+#[test]
+fn test_gt_le_encoding() {
+    use rand_core::SeedableRng;
+
+    let g = Gt::generator();
+    let bytes = g.to_bytes_le();
+    let mut first = g.0.c0.c0.c0.to_bytes();
+    first.reverse();
+    assert_eq!(&bytes[..48], &first[..]);
+    assert_eq!(Gt::from_bytes_le(&bytes, Validate::Yes).unwrap(), g);
+
+    assert!(bool::from(g.is_torsion_free()));
+    assert!(bool::from(Gt::IDENTITY.is_torsion_free()));
+
+    let f = Gt(Fp12::random(&mut rand_xorshift::XorShiftRng::from_seed(
+        [33u8; 16],
+    )));
+    assert!(!bool::from(f.is_torsion_free()));
+    let bytes = f.to_bytes_le();
+    assert!(bool::from(
+        Gt::from_bytes_le(&bytes, Validate::Yes).is_none()
+    ));
+    assert_eq!(Gt::from_bytes_le(&bytes, Validate::No).unwrap(), f);
+}
+
+#[cfg(feature = "expose-fields")]
+#[test]
+fn test_as_fp12() {
+    let gt = pairing(&G1Affine::generator(), &G2Affine::generator());
+    let ml = multi_miller_loop(&[(
+        &G1Affine::generator(),
+        &G2Prepared::from(G2Affine::generator()),
+    )]);
+    assert_eq!(ml.final_exponentiation(), gt);
+    assert_eq!(gt.as_fp12().to_bytes(), gt.to_bytes());
+    assert_eq!(ml.as_fp12().to_bytes(), ml.to_bytes());
+}
+
+#[test]
+fn test_try_from_bytes() {
+    let gt = pairing(&G1Affine::generator(), &G2Affine::generator());
+    assert_eq!(Gt::try_from(&gt.to_bytes()[..]), Ok(gt));
+    let ml = MillerLoopResult(gt.0);
+    assert_eq!(
+        MillerLoopResult::try_from(&ml.to_bytes()[..]).map(|m| m.0),
+        Ok(gt.0)
+    );
+
+    let zero = [0u8; Gt::BYTES];
+    assert_eq!(
+        MillerLoopResult::try_from(&zero[..]).map(|m| m.0),
+        Err(DecodingError::Zero)
+    );
+
+    let invalid = [0xffu8; Gt::BYTES];
+    assert_eq!(
+        MillerLoopResult::try_from(&invalid[..]).map(|m| m.0),
+        Err(DecodingError::NonCanonicalField)
+    );
+    assert_eq!(
+        Gt::try_from(&invalid[..]),
+        Err(DecodingError::NonCanonicalField)
+    );
+}
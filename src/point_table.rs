@@ -0,0 +1,666 @@
+//! This module provides a streaming encoding for large tables of points, such
+//! as the structured reference strings used by polynomial commitment schemes.
+//!
+//! A table is a 16-byte header followed by `count` points, each in the
+//! encoding described in [`notes::serialization`](crate::notes::serialization),
+//! that is, as the bytes of `G1Compressed` or `G1Uncompressed` or their
+//! $\mathbb{G}_2$ equivalents. The header is:
+//!
+//! * the four bytes `b"B381"`,
+//! * a version byte, currently `1`,
+//! * a curve byte, `1` for $\mathbb{G}_1$ and `2` for $\mathbb{G}_2$,
+//! * an encoding byte, `0` for compressed and `1` for uncompressed points,
+//! * a reserved byte, which must be zero,
+//! * the number of points as a little-endian `u64`.
+//!
+//! [`PointTableWriter`] and [`PointTableReader`] stream tables through
+//! [`std::io`], and [`PointTableRef`] decodes points on demand from a byte
+//! slice, such as a memory-mapped file. This crate does not itself map files;
+//! the bytes can be obtained from any memory-mapping library.
+//!
+//! When validating, points are first decoded without checks and then checked
+//! to be on the curve and in the prime order subgroup in batches, spread across
+//! the available threads.
+
+use arrayref::array_ref;
+use core::fmt::{self, Display, Formatter};
+use core::marker::PhantomData;
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+use group::prime::PrimeCurveAffine;
+
+use crate::arkworks::Validate;
+use crate::{DecodingError, G1Affine, G2Affine};
+
+/// The magic bytes at the start of every table.
+const MAGIC: [u8; 4] = *b"B381";
+/// The current version of the table format.
+const VERSION: u8 = 1;
+/// The number of points decoded at a time by [`PointTableReader::read_all`].
+const BATCH_SIZE: usize = 1 << 16;
+
+/// The group the points of a table belong to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TableCurve {
+    /// Points of $\mathbb{G}_1$
+    G1,
+    /// Points of $\mathbb{G}_2$
+    G2,
+}
+
+impl TableCurve {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::G1 => 1,
+            Self::G2 => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            1 => Some(Self::G1),
+            2 => Some(Self::G2),
+            _ => None,
+        }
+    }
+}
+
+/// How the points of a table are encoded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PointEncoding {
+    /// Points are encoded with `to_compressed`, which halves the size of the
+    /// table but requires a square root to decode each point.
+    Compressed,
+    /// Points are encoded with `to_uncompressed`, which doubles the size of
+    /// the table but avoids the square root when decoding each point.
+    Uncompressed,
+}
+
+impl PointEncoding {
+    fn to_byte(self) -> u8 {
+        match self {
+            Self::Compressed => 0,
+            Self::Uncompressed => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Compressed),
+            1 => Some(Self::Uncompressed),
+            _ => None,
+        }
+    }
+}
+
+/// The header at the start of a table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TableHeader {
+    /// The group the points belong to
+    pub curve: TableCurve,
+    /// How the points are encoded
+    pub encoding: PointEncoding,
+    /// The number of points in the table
+    pub count: u64,
+}
+
+impl TableHeader {
+    /// Bytes in the encoded header
+    pub const BYTES: usize = 16;
+
+    /// Encodes the header.
+    pub fn to_bytes(&self) -> [u8; Self::BYTES] {
+        let mut bytes = [0u8; Self::BYTES];
+        bytes[..4].copy_from_slice(&MAGIC);
+        bytes[4] = VERSION;
+        bytes[5] = self.curve.to_byte();
+        bytes[6] = self.encoding.to_byte();
+        bytes[8..].copy_from_slice(&self.count.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes a header, checking the magic bytes and version.
+    pub fn from_bytes(bytes: &[u8; Self::BYTES]) -> Result<Self, TableError> {
+        if bytes[..4] != MAGIC {
+            return Err(TableError::InvalidHeader);
+        }
+        if bytes[4] != VERSION {
+            return Err(TableError::UnsupportedVersion(bytes[4]));
+        }
+        let curve = TableCurve::from_byte(bytes[5]).ok_or(TableError::InvalidHeader)?;
+        let encoding = PointEncoding::from_byte(bytes[6]).ok_or(TableError::InvalidHeader)?;
+        if bytes[7] != 0 {
+            return Err(TableError::InvalidHeader);
+        }
+        let count = u64::from_le_bytes(*array_ref![bytes, 8, 8]);
+        Ok(Self {
+            curve,
+            encoding,
+            count,
+        })
+    }
+
+    /// Returns the number of bytes in each encoded point.
+    pub fn point_bytes(&self) -> usize {
+        match (self.curve, self.encoding) {
+            (TableCurve::G1, PointEncoding::Compressed) => G1Affine::COMPRESSED_BYTES,
+            (TableCurve::G1, PointEncoding::Uncompressed) => G1Affine::UNCOMPRESSED_BYTES,
+            (TableCurve::G2, PointEncoding::Compressed) => G2Affine::COMPRESSED_BYTES,
+            (TableCurve::G2, PointEncoding::Uncompressed) => G2Affine::UNCOMPRESSED_BYTES,
+        }
+    }
+}
+
+/// The reasons reading a table can fail.
+#[derive(Debug)]
+pub enum TableError {
+    /// The underlying reader failed, including if the table ended early.
+    Io(io::Error),
+    /// The header is malformed.
+    InvalidHeader,
+    /// The header has a version this crate does not support.
+    UnsupportedVersion(u8),
+    /// The table holds points of a different group than requested.
+    CurveMismatch {
+        /// The group requested
+        expected: TableCurve,
+        /// The group in the header
+        found: TableCurve,
+    },
+    /// The number of bytes does not match the header.
+    InvalidLength,
+    /// A point failed to decode.
+    InvalidPoint {
+        /// The index of the first invalid point
+        index: u64,
+        /// Why the point is invalid
+        error: DecodingError,
+    },
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "i/o error: {}", e),
+            Self::InvalidHeader => write!(f, "invalid table header"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported table version {}", v),
+            Self::CurveMismatch { expected, found } => write!(
+                f,
+                "table holds points of {:?}, expected {:?}",
+                found, expected
+            ),
+            Self::InvalidLength => write!(f, "table length does not match the header"),
+            Self::InvalidPoint { index, error } => write!(f, "point {}: {}", index, error),
+        }
+    }
+}
+
+impl std::error::Error for TableError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::InvalidPoint { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TableError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+mod private {
+    use super::*;
+
+    pub trait Sealed: Sized {
+        fn encode(&self, encoding: PointEncoding, out: &mut Vec<u8>);
+
+        fn decode(
+            bytes: &[u8],
+            encoding: PointEncoding,
+            validate: Validate,
+        ) -> Result<Self, DecodingError>;
+
+        fn check(&self) -> Result<(), DecodingError>;
+    }
+}
+
+/// A point which can be stored in a table, implemented for [`G1Affine`] and
+/// [`G2Affine`].
+pub trait TablePoint: PrimeCurveAffine + Send + Sync + private::Sealed {
+    /// The group identifier written to the header
+    const CURVE: TableCurve;
+}
+
+macro_rules! impl_table_point {
+    ($name:ident, $curve:expr) => {
+        impl TablePoint for $name {
+            const CURVE: TableCurve = $curve;
+        }
+
+        impl private::Sealed for $name {
+            fn encode(&self, encoding: PointEncoding, out: &mut Vec<u8>) {
+                match encoding {
+                    PointEncoding::Compressed => out.extend_from_slice(&self.to_compressed()),
+                    PointEncoding::Uncompressed => out.extend_from_slice(&self.to_uncompressed()),
+                }
+            }
+
+            fn decode(
+                bytes: &[u8],
+                encoding: PointEncoding,
+                validate: Validate,
+            ) -> Result<Self, DecodingError> {
+                match (encoding, validate) {
+                    (PointEncoding::Compressed, Validate::Yes) => {
+                        $name::try_from_compressed(&DecodingError::to_array(bytes)?)
+                    }
+                    (PointEncoding::Uncompressed, Validate::Yes) => {
+                        $name::try_from_uncompressed(&DecodingError::to_array(bytes)?)
+                    }
+                    (PointEncoding::Compressed, Validate::No) => {
+                        let bytes = DecodingError::to_array(bytes)?;
+                        Option::from($name::from_compressed_unchecked(&bytes))
+                            .ok_or_else(|| $name::try_from_compressed(&bytes).unwrap_err())
+                    }
+                    (PointEncoding::Uncompressed, Validate::No) => {
+                        let bytes = DecodingError::to_array(bytes)?;
+                        Option::from($name::from_uncompressed_unchecked(&bytes))
+                            .ok_or_else(|| $name::try_from_uncompressed(&bytes).unwrap_err())
+                    }
+                }
+            }
+
+            fn check(&self) -> Result<(), DecodingError> {
+                if !bool::from(self.is_on_curve()) {
+                    Err(DecodingError::NotOnCurve)
+                } else if !bool::from(self.is_torsion_free()) {
+                    Err(DecodingError::NotInSubgroup)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    };
+}
+
+impl_table_point!(G1Affine, TableCurve::G1);
+impl_table_point!(G2Affine, TableCurve::G2);
+
+/// Checks that every point is on the curve and in the prime order subgroup,
+/// splitting the points across the available threads. `first_index` is the
+/// index in the table of `points[0]`, used when reporting an invalid point.
+pub fn validate_points<P: TablePoint>(points: &[P], first_index: u64) -> Result<(), TableError> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = points.len().div_ceil(threads).max(1);
+
+    let first_invalid = std::thread::scope(|s| {
+        let handles = points
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(i, chunk)| {
+                s.spawn(move || {
+                    chunk
+                        .iter()
+                        .enumerate()
+                        .find_map(|(j, p)| p.check().err().map(|error| (i * chunk_size + j, error)))
+                })
+            })
+            .collect::<Vec<_>>();
+        // Chunks are in order, so the first failure found is the earliest.
+        handles
+            .into_iter()
+            .filter_map(|h| h.join().expect("validation thread panicked"))
+            .next()
+    });
+
+    match first_invalid {
+        Some((index, error)) => Err(TableError::InvalidPoint {
+            index: first_index + index as u64,
+            error,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Writes a table of points to a [`Write`], one point at a time.
+///
+/// The number of points is written to the header up front, so it must be
+/// known before writing.
+#[derive(Debug)]
+pub struct PointTableWriter<W: Write, P: TablePoint> {
+    writer: W,
+    encoding: PointEncoding,
+    remaining: u64,
+    buffer: Vec<u8>,
+    _marker: PhantomData<P>,
+}
+
+impl<W: Write, P: TablePoint> PointTableWriter<W, P> {
+    /// Writes the header for a table of `count` points.
+    pub fn new(mut writer: W, encoding: PointEncoding, count: u64) -> io::Result<Self> {
+        let header = TableHeader {
+            curve: P::CURVE,
+            encoding,
+            count,
+        };
+        writer.write_all(&header.to_bytes())?;
+        Ok(Self {
+            writer,
+            encoding,
+            remaining: count,
+            buffer: Vec::new(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Writes the next points of the table, failing if this would exceed the
+    /// count given to [`new`](Self::new).
+    pub fn write_points(&mut self, points: &[P]) -> io::Result<()> {
+        if points.len() as u64 > self.remaining {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "more points written than declared in the header",
+            ));
+        }
+        self.buffer.clear();
+        for p in points {
+            p.encode(self.encoding, &mut self.buffer);
+        }
+        self.writer.write_all(&self.buffer)?;
+        self.remaining -= points.len() as u64;
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying writer, failing if fewer points
+    /// were written than declared in the header.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.remaining != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "fewer points written than declared in the header",
+            ));
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads a table of points from a [`Read`] in batches.
+#[derive(Debug)]
+pub struct PointTableReader<R: Read, P: TablePoint> {
+    reader: R,
+    header: TableHeader,
+    validate: Validate,
+    index: u64,
+    buffer: Vec<u8>,
+    _marker: PhantomData<P>,
+}
+
+impl<R: Read, P: TablePoint> PointTableReader<R, P> {
+    /// Reads and checks the header. Points are checked to be on the curve and
+    /// in the prime order subgroup if `validate` is [`Validate::Yes`].
+    pub fn new(mut reader: R, validate: Validate) -> Result<Self, TableError> {
+        let mut bytes = [0u8; TableHeader::BYTES];
+        reader.read_exact(&mut bytes)?;
+        let header = TableHeader::from_bytes(&bytes)?;
+        if header.curve != P::CURVE {
+            return Err(TableError::CurveMismatch {
+                expected: P::CURVE,
+                found: header.curve,
+            });
+        }
+        Ok(Self {
+            reader,
+            header,
+            validate,
+            index: 0,
+            buffer: Vec::new(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the header of the table.
+    pub fn header(&self) -> TableHeader {
+        self.header
+    }
+
+    /// Returns the number of points not yet read.
+    pub fn remaining(&self) -> u64 {
+        self.header.count - self.index
+    }
+
+    /// Reads up to `max` of the remaining points, returning an empty vector
+    /// once the table is exhausted.
+    pub fn read_batch(&mut self, max: usize) -> Result<Vec<P>, TableError> {
+        let n = (max as u64).min(self.remaining()) as usize;
+        let point_bytes = self.header.point_bytes();
+        self.buffer.resize(n * point_bytes, 0);
+        self.reader.read_exact(&mut self.buffer)?;
+
+        let points = self
+            .buffer
+            .chunks_exact(point_bytes)
+            .enumerate()
+            .map(|(i, bytes)| {
+                P::decode(bytes, self.header.encoding, Validate::No).map_err(|error| {
+                    TableError::InvalidPoint {
+                        index: self.index + i as u64,
+                        error,
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if self.validate == Validate::Yes {
+            validate_points(&points, self.index)?;
+        }
+        self.index += n as u64;
+        Ok(points)
+    }
+
+    /// Reads all of the remaining points.
+    pub fn read_all(mut self) -> Result<Vec<P>, TableError> {
+        let mut points = Vec::with_capacity(self.remaining().min(BATCH_SIZE as u64) as usize);
+        while self.remaining() > 0 {
+            points.append(&mut self.read_batch(BATCH_SIZE)?);
+        }
+        Ok(points)
+    }
+}
+
+/// A table of points borrowed from a byte slice, such as a memory-mapped
+/// file, which decodes points on demand.
+#[derive(Debug)]
+pub struct PointTableRef<'a, P: TablePoint> {
+    header: TableHeader,
+    points: &'a [u8],
+    _marker: PhantomData<P>,
+}
+
+impl<'a, P: TablePoint> PointTableRef<'a, P> {
+    /// Checks the header and that `bytes` holds exactly the number of points
+    /// it declares. No points are decoded.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, TableError> {
+        if bytes.len() < TableHeader::BYTES {
+            return Err(TableError::InvalidLength);
+        }
+        let header = TableHeader::from_bytes(array_ref![bytes, 0, TableHeader::BYTES])?;
+        if header.curve != P::CURVE {
+            return Err(TableError::CurveMismatch {
+                expected: P::CURVE,
+                found: header.curve,
+            });
+        }
+        let points = &bytes[TableHeader::BYTES..];
+        if header.count.checked_mul(header.point_bytes() as u64) != Some(points.len() as u64) {
+            return Err(TableError::InvalidLength);
+        }
+        Ok(Self {
+            header,
+            points,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the header of the table.
+    pub fn header(&self) -> TableHeader {
+        self.header
+    }
+
+    /// Returns the number of points in the table.
+    pub fn len(&self) -> usize {
+        self.header.count as usize
+    }
+
+    /// Returns true if the table has no points.
+    pub fn is_empty(&self) -> bool {
+        self.header.count == 0
+    }
+
+    /// Returns the encoded bytes of the point at `index`, or `None` if it is
+    /// out of bounds.
+    pub fn get_bytes(&self, index: usize) -> Option<&'a [u8]> {
+        let point_bytes = self.header.point_bytes();
+        let start = index.checked_mul(point_bytes)?;
+        self.points.get(start..start.checked_add(point_bytes)?)
+    }
+
+    /// Decodes the point at `index`, or returns `None` if it is out of bounds.
+    pub fn get(&self, index: usize, validate: Validate) -> Option<Result<P, TableError>> {
+        self.get_bytes(index).map(|bytes| {
+            P::decode(bytes, self.header.encoding, validate).map_err(|error| {
+                TableError::InvalidPoint {
+                    index: index as u64,
+                    error,
+                }
+            })
+        })
+    }
+
+    /// Decodes every point in the table, validating them in batches if
+    /// `validate` is [`Validate::Yes`].
+    pub fn to_vec(&self, validate: Validate) -> Result<Vec<P>, TableError> {
+        let points = (0..self.len())
+            .map(|i| self.get(i, Validate::No).unwrap())
+            .collect::<Result<Vec<_>, _>>()?;
+        if validate == Validate::Yes {
+            validate_points(&points, 0)?;
+        }
+        Ok(points)
+    }
+}
+
+#[test]
+fn test_point_table_roundtrip() {
+    use group::Curve;
+
+    let g1 = (0..10u64)
+        .map(|i| (G1Affine::generator() * crate::Scalar::from(i)).to_affine())
+        .collect::<Vec<_>>();
+    let g2 = (0..10u64)
+        .map(|i| (G2Affine::generator() * crate::Scalar::from(i)).to_affine())
+        .collect::<Vec<_>>();
+
+    for encoding in [PointEncoding::Compressed, PointEncoding::Uncompressed] {
+        let mut writer = PointTableWriter::new(Vec::new(), encoding, 10).unwrap();
+        writer.write_points(&g1[..3]).unwrap();
+        writer.write_points(&g1[3..]).unwrap();
+        let bytes = writer.finish().unwrap();
+        let point_bytes = match encoding {
+            PointEncoding::Compressed => 48,
+            PointEncoding::Uncompressed => 96,
+        };
+        assert_eq!(bytes.len(), TableHeader::BYTES + 10 * point_bytes);
+
+        let mut reader = PointTableReader::<_, G1Affine>::new(&bytes[..], Validate::Yes).unwrap();
+        assert_eq!(reader.header().count, 10);
+        assert_eq!(reader.read_batch(4).unwrap(), &g1[..4]);
+        assert_eq!(reader.read_all().unwrap(), &g1[4..]);
+
+        let table = PointTableRef::<G1Affine>::new(&bytes).unwrap();
+        assert_eq!(table.len(), 10);
+        assert_eq!(table.get(7, Validate::Yes).unwrap().unwrap(), g1[7]);
+        assert!(table.get(10, Validate::Yes).is_none());
+        assert!(table.get_bytes(usize::MAX / point_bytes).is_none());
+        assert_eq!(table.to_vec(Validate::Yes).unwrap(), g1);
+
+        assert!(matches!(
+            PointTableReader::<_, G2Affine>::new(&bytes[..], Validate::Yes),
+            Err(TableError::CurveMismatch { .. })
+        ));
+        assert!(matches!(
+            PointTableRef::<G1Affine>::new(&bytes[..bytes.len() - 1]),
+            Err(TableError::InvalidLength)
+        ));
+        assert!(matches!(
+            PointTableReader::<_, G1Affine>::new(&bytes[..bytes.len() - 1], Validate::Yes)
+                .unwrap()
+                .read_all(),
+            Err(TableError::Io(_))
+        ));
+
+        let mut writer = PointTableWriter::new(Vec::new(), encoding, 10).unwrap();
+        writer.write_points(&g2).unwrap();
+        let bytes = writer.finish().unwrap();
+        let reader = PointTableReader::<_, G2Affine>::new(&bytes[..], Validate::Yes).unwrap();
+        assert_eq!(reader.read_all().unwrap(), g2);
+    }
+
+    let mut writer = PointTableWriter::new(Vec::new(), PointEncoding::Compressed, 2).unwrap();
+    assert!(writer.write_points(&g1[..3]).is_err());
+    writer.write_points(&g1[..1]).unwrap();
+    assert!(writer.finish().is_err());
+}
+
+#[test]
+fn test_point_table_validation() {
+    // (0, 2) is on the curve but not in the prime order subgroup.
+    let mut bad = [0u8; 96];
+    bad[95] = 2;
+    let mut bytes = TableHeader {
+        curve: TableCurve::G1,
+        encoding: PointEncoding::Uncompressed,
+        count: 3,
+    }
+    .to_bytes()
+    .to_vec();
+    bytes.extend_from_slice(&G1Affine::generator().to_uncompressed());
+    bytes.extend_from_slice(&bad);
+    bytes.extend_from_slice(&G1Affine::identity().to_uncompressed());
+
+    let table = PointTableRef::<G1Affine>::new(&bytes).unwrap();
+    assert!(table.get(1, Validate::No).unwrap().is_ok());
+    assert!(matches!(
+        table.get(1, Validate::Yes).unwrap(),
+        Err(TableError::InvalidPoint {
+            index: 1,
+            error: DecodingError::NotInSubgroup
+        })
+    ));
+    assert_eq!(table.to_vec(Validate::No).unwrap().len(), 3);
+    assert!(matches!(
+        table.to_vec(Validate::Yes),
+        Err(TableError::InvalidPoint {
+            index: 1,
+            error: DecodingError::NotInSubgroup
+        })
+    ));
+
+    let reader = PointTableReader::<_, G1Affine>::new(&bytes[..], Validate::Yes).unwrap();
+    assert!(matches!(
+        reader.read_all(),
+        Err(TableError::InvalidPoint {
+            index: 1,
+            error: DecodingError::NotInSubgroup
+        })
+    ));
+
+    bytes[0] = b'C';
+    assert!(matches!(
+        PointTableRef::<G1Affine>::new(&bytes),
+        Err(TableError::InvalidHeader)
+    ));
+}
@@ -0,0 +1,440 @@
+//! This module provides dense univariate polynomials over the scalar field.
+//!
+//! A [`Polynomial`] holds its coefficients from the constant term upwards,
+//! without trailing zeros, so the zero polynomial has no coefficients.
+//! Products of large polynomials and conversions to and from evaluations
+//! over an [`EvaluationDomain`] use number theoretic transforms.
+//!
+//! Arithmetic is not constant time: results are trimmed of trailing zero
+//! coefficients, which branches on their values and reveals cancellation of
+//! the leading terms through the length of the result. Interpolation is also
+//! variable time with respect to the points.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use ff::Field;
+use rand_core::RngCore;
+
+use crate::{EvaluationDomain, Scalar};
+
+/// The size of the smaller factor up to which products are computed with
+/// schoolbook multiplication rather than transforms.
+const SCHOOLBOOK_MAX_LEN: usize = 64;
+
+/// A univariate polynomial over the scalar field, stored as its coefficients.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Polynomial {
+    coeffs: Vec<Scalar>,
+}
+
+impl From<Vec<Scalar>> for Polynomial {
+    fn from(coeffs: Vec<Scalar>) -> Self {
+        Self::from_coefficients(coeffs)
+    }
+}
+
+impl Polynomial {
+    /// The zero polynomial
+    pub const ZERO: Polynomial = Polynomial { coeffs: Vec::new() };
+
+    /// Creates a polynomial from its coefficients, starting with the
+    /// constant term. Trailing zeros are removed.
+    pub fn from_coefficients(coeffs: Vec<Scalar>) -> Self {
+        let mut p = Self { coeffs };
+        p.trim();
+        p
+    }
+
+    /// Creates a random polynomial of the given degree, whose coefficients
+    /// may all be zero with negligible probability.
+    pub fn random(degree: usize, mut rng: impl RngCore) -> Self {
+        Self::from_coefficients((0..=degree).map(|_| Scalar::random(&mut rng)).collect())
+    }
+
+    /// Returns the vanishing polynomial X^n - 1 of `domain`, which is zero
+    /// exactly on its n elements.
+    pub fn vanishing(domain: &EvaluationDomain) -> Self {
+        let mut coeffs = vec![Scalar::ZERO; domain.size() + 1];
+        coeffs[0] = -Scalar::ONE;
+        coeffs[domain.size()] = Scalar::ONE;
+        Self { coeffs }
+    }
+
+    /// Returns the coefficients, starting with the constant term.
+    pub fn coefficients(&self) -> &[Scalar] {
+        &self.coeffs
+    }
+
+    /// Returns the coefficients, starting with the constant term.
+    pub fn into_coefficients(self) -> Vec<Scalar> {
+        self.coeffs
+    }
+
+    /// Returns true if this is the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// Returns the degree of the polynomial, where the zero polynomial has
+    /// degree 0.
+    pub fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+
+    /// Evaluates the polynomial at `x` with Horner's rule.
+    pub fn evaluate(&self, x: &Scalar) -> Scalar {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Scalar::ZERO, |acc, c| acc * x + c)
+    }
+
+    /// Evaluates the polynomial at each of `points`.
+    pub fn evaluate_many(&self, points: &[Scalar]) -> Vec<Scalar> {
+        points.iter().map(|x| self.evaluate(x)).collect()
+    }
+
+    /// Evaluates the polynomial at every element of `domain`, in order.
+    pub fn evaluate_over_domain(&self, domain: &EvaluationDomain) -> Vec<Scalar> {
+        // Reduce modulo X^n - 1, which does not change the evaluations
+        let mut values = vec![Scalar::ZERO; domain.size()];
+        for (i, c) in self.coeffs.iter().enumerate() {
+            values[i % domain.size()] += c;
+        }
+        domain.fft(&mut values);
+        values
+    }
+
+    /// Returns the polynomial of degree less than n taking the values
+    /// `evaluations` at the elements of `domain`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `evaluations` does not have exactly n elements.
+    pub fn interpolate_over_domain(domain: &EvaluationDomain, evaluations: &[Scalar]) -> Self {
+        let mut coeffs = evaluations.to_vec();
+        domain.ifft(&mut coeffs);
+        Self::from_coefficients(coeffs)
+    }
+
+    /// Returns the Lagrange interpolation polynomial of least degree taking
+    /// the values `ys` at the points `xs`, or `None` if two points are equal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `xs` and `ys` have different lengths.
+    pub fn interpolate(xs: &[Scalar], ys: &[Scalar]) -> Option<Self> {
+        assert_eq!(xs.len(), ys.len(), "the number of points and values differ");
+
+        // Z(X) = prod (X - x_j)
+        let mut z = vec![Scalar::ONE];
+        for x in xs {
+            z.push(Scalar::ZERO);
+            for k in (1..z.len()).rev() {
+                z[k] = z[k - 1] - z[k] * x;
+            }
+            z[0] = -z[0] * x;
+        }
+        let z = Self { coeffs: z };
+
+        // The barycentric weights 1 / prod_{j != i} (x_i - x_j)
+        let mut weights = xs
+            .iter()
+            .enumerate()
+            .map(|(i, x_i)| {
+                xs.iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .fold(Scalar::ONE, |acc, (_, x_j)| acc * (x_i - x_j))
+            })
+            .collect::<Vec<_>>();
+        if weights.iter().any(|w| bool::from(w.is_zero())) {
+            return None;
+        }
+        Scalar::batch_invert_vartime(&mut weights);
+
+        let mut coeffs = vec![Scalar::ZERO; xs.len()];
+        for ((x_i, y_i), w_i) in xs.iter().zip(ys).zip(weights) {
+            let (basis, _) = z.divide_by_linear(x_i);
+            let scale = y_i * w_i;
+            for (c, b) in coeffs.iter_mut().zip(basis.coeffs.iter()) {
+                *c += b * scale;
+            }
+        }
+        Some(Self::from_coefficients(coeffs))
+    }
+
+    /// Multiplies two polynomials with the schoolbook method, in time
+    /// quadratic in their sizes.
+    pub fn mul_schoolbook(&self, rhs: &Polynomial) -> Self {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::ZERO;
+        }
+        let mut coeffs = vec![Scalar::ZERO; self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (c, b) in coeffs[i..].iter_mut().zip(rhs.coeffs.iter()) {
+                *c += a * b;
+            }
+        }
+        Self::from_coefficients(coeffs)
+    }
+
+    /// Multiplies two polynomials with number theoretic transforms over the
+    /// smallest domain that holds the product.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product has more than 2^32 coefficients.
+    pub fn mul_ntt(&self, rhs: &Polynomial) -> Self {
+        if self.is_zero() || rhs.is_zero() {
+            return Self::ZERO;
+        }
+        let len = self.coeffs.len() + rhs.coeffs.len() - 1;
+        let domain = EvaluationDomain::new(len).expect("the product is too large for a domain");
+
+        let mut a = self.coeffs.clone();
+        a.resize(domain.size(), Scalar::ZERO);
+        domain.fft(&mut a);
+        let mut b = rhs.coeffs.clone();
+        b.resize(domain.size(), Scalar::ZERO);
+        domain.fft(&mut b);
+
+        for (a_i, b_i) in a.iter_mut().zip(b.iter()) {
+            *a_i *= b_i;
+        }
+        domain.ifft(&mut a);
+        a.truncate(len);
+        Self::from_coefficients(a)
+    }
+
+    /// Divides by X - `z`, returning the quotient and the remainder, which is
+    /// the evaluation at `z`.
+    pub fn divide_by_linear(&self, z: &Scalar) -> (Self, Scalar) {
+        let Some((leading, rest)) = self.coeffs.split_last() else {
+            return (Self::ZERO, Scalar::ZERO);
+        };
+
+        // Synthetic division, from the leading coefficient down
+        let mut quotient = vec![Scalar::ZERO; rest.len()];
+        let mut carry = *leading;
+        for (q, c) in quotient.iter_mut().zip(rest.iter()).rev() {
+            *q = carry;
+            carry = c + carry * z;
+        }
+        (Self::from_coefficients(quotient), carry)
+    }
+
+    /// Divides by the vanishing polynomial X^n - 1 of `domain`, returning the
+    /// quotient and the remainder.
+    pub fn divide_by_vanishing_polynomial(&self, domain: &EvaluationDomain) -> (Self, Self) {
+        let n = domain.size();
+        if self.coeffs.len() <= n {
+            return (Self::ZERO, self.clone());
+        }
+
+        // Each X^i with i >= n contributes X^(i - n) to the quotient and is
+        // replaced by X^(i - n) in the remainder.
+        let mut remainder = self.coeffs.clone();
+        let mut quotient = vec![Scalar::ZERO; remainder.len() - n];
+        for i in (n..remainder.len()).rev() {
+            quotient[i - n] = remainder[i];
+            let c = remainder[i];
+            remainder[i - n] += c;
+        }
+        remainder.truncate(n);
+        (
+            Self::from_coefficients(quotient),
+            Self::from_coefficients(remainder),
+        )
+    }
+
+    /// Removes trailing zero coefficients.
+    fn trim(&mut self) {
+        while self.coeffs.last().is_some_and(|c| bool::from(c.is_zero())) {
+            self.coeffs.pop();
+        }
+    }
+}
+
+impl<'a> Neg for &'a Polynomial {
+    type Output = Polynomial;
+
+    #[inline]
+    fn neg(self) -> Polynomial {
+        Polynomial {
+            coeffs: self.coeffs.iter().map(|c| -c).collect(),
+        }
+    }
+}
+
+impl Neg for Polynomial {
+    type Output = Polynomial;
+
+    #[inline]
+    fn neg(self) -> Polynomial {
+        -&self
+    }
+}
+
+impl<'a, 'b> Add<&'b Polynomial> for &'a Polynomial {
+    type Output = Polynomial;
+
+    #[inline]
+    fn add(self, rhs: &'b Polynomial) -> Polynomial {
+        let (long, short) = if self.coeffs.len() >= rhs.coeffs.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+        let mut coeffs = long.coeffs.clone();
+        for (c, s) in coeffs.iter_mut().zip(short.coeffs.iter()) {
+            *c += s;
+        }
+        Polynomial::from_coefficients(coeffs)
+    }
+}
+
+impl<'a, 'b> Sub<&'b Polynomial> for &'a Polynomial {
+    type Output = Polynomial;
+
+    #[inline]
+    fn sub(self, rhs: &'b Polynomial) -> Polynomial {
+        self + &(-rhs)
+    }
+}
+
+impl<'a, 'b> Mul<&'b Polynomial> for &'a Polynomial {
+    type Output = Polynomial;
+
+    /// Multiplies with the schoolbook method when either factor is small, and
+    /// with transforms otherwise.
+    #[inline]
+    fn mul(self, rhs: &'b Polynomial) -> Polynomial {
+        if self.coeffs.len().min(rhs.coeffs.len()) <= SCHOOLBOOK_MAX_LEN {
+            self.mul_schoolbook(rhs)
+        } else {
+            self.mul_ntt(rhs)
+        }
+    }
+}
+
+impl<'a, 'b> Mul<&'b Scalar> for &'a Polynomial {
+    type Output = Polynomial;
+
+    #[inline]
+    fn mul(self, rhs: &'b Scalar) -> Polynomial {
+        Polynomial::from_coefficients(self.coeffs.iter().map(|c| c * rhs).collect())
+    }
+}
+
+impl_binops_additive!(Polynomial, Polynomial);
+impl_binops_multiplicative!(Polynomial, Polynomial);
+impl_binops_multiplicative_mixed!(Polynomial, Scalar, Polynomial);
+
+#[test]
+fn test_arithmetic() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let a = Polynomial::random(10, &mut rng);
+    let b = Polynomial::random(6, &mut rng);
+    let x = Scalar::random(&mut rng);
+
+    assert_eq!((&a + &b).evaluate(&x), a.evaluate(&x) + b.evaluate(&x));
+    assert_eq!((&a - &b).evaluate(&x), a.evaluate(&x) - b.evaluate(&x));
+    assert_eq!((&a * &b).evaluate(&x), a.evaluate(&x) * b.evaluate(&x));
+    assert_eq!((&a * x).evaluate(&x), a.evaluate(&x) * x);
+    assert_eq!((&a * &b).degree(), 16);
+    assert_eq!(-(-a.clone()), a);
+
+    // Cancellation trims the leading coefficients
+    assert!((&a - &a).is_zero());
+    assert_eq!((&a - &a).degree(), 0);
+    let mut c = a.clone();
+    c -= Polynomial::from_coefficients(a.coefficients()[..5].to_vec());
+    assert_eq!(c.degree(), 10);
+    c -= &a;
+    assert_eq!(c.degree(), 4);
+    assert_eq!(&a * &Polynomial::ZERO, Polynomial::ZERO);
+    assert_eq!(
+        Polynomial::from_coefficients(vec![Scalar::ONE, Scalar::ZERO]).coefficients(),
+        &[Scalar::ONE]
+    );
+}
+
+#[test]
+fn test_mul_ntt() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    for (da, db) in [(0, 0), (1, 0), (3, 4), (100, 70), (64, 200)] {
+        let a = Polynomial::random(da, &mut rng);
+        let b = Polynomial::random(db, &mut rng);
+        let expected = a.mul_schoolbook(&b);
+        assert_eq!(a.mul_ntt(&b), expected);
+        assert_eq!(&a * &b, expected);
+    }
+    assert!(Polynomial::ZERO
+        .mul_ntt(&Polynomial::random(3, &mut rng))
+        .is_zero());
+}
+
+#[test]
+fn test_division() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let p = Polynomial::random(20, &mut rng);
+    let z = Scalar::random(&mut rng);
+
+    let (q, r) = p.divide_by_linear(&z);
+    assert_eq!(r, p.evaluate(&z));
+    let linear = Polynomial::from_coefficients(vec![-z, Scalar::ONE]);
+    assert_eq!(&q * &linear + Polynomial::from_coefficients(vec![r]), p);
+    assert_eq!(
+        Polynomial::ZERO.divide_by_linear(&z),
+        (Polynomial::ZERO, Scalar::ZERO)
+    );
+
+    for log_size in [0, 2, 3, 5] {
+        let domain = EvaluationDomain::from_log_size(log_size).unwrap();
+        let (q, r) = p.divide_by_vanishing_polynomial(&domain);
+        assert!(r.coefficients().len() <= domain.size());
+        assert_eq!(&q * &Polynomial::vanishing(&domain) + &r, p);
+    }
+}
+
+#[test]
+fn test_interpolation() {
+    use rand_core::SeedableRng;
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let p = Polynomial::random(7, &mut rng);
+
+    let xs = (0..8).map(|_| Scalar::random(&mut rng)).collect::<Vec<_>>();
+    let ys = p.evaluate_many(&xs);
+    assert_eq!(Polynomial::interpolate(&xs, &ys), Some(p.clone()));
+    assert_eq!(Polynomial::interpolate(&[], &[]), Some(Polynomial::ZERO));
+
+    let mut repeated = xs.clone();
+    repeated[3] = repeated[5];
+    assert_eq!(Polynomial::interpolate(&repeated, &ys), None);
+
+    let domain = EvaluationDomain::new(8).unwrap();
+    let evaluations = p.evaluate_over_domain(&domain);
+    assert_eq!(
+        evaluations,
+        p.evaluate_many(&domain.elements().collect::<Vec<_>>())
+    );
+    assert_eq!(
+        Polynomial::interpolate_over_domain(&domain, &evaluations),
+        p
+    );
+
+    // Larger polynomials are reduced modulo the vanishing polynomial
+    let q = Polynomial::random(20, &mut rng);
+    assert_eq!(
+        q.evaluate_over_domain(&domain),
+        q.divide_by_vanishing_polynomial(&domain)
+            .1
+            .evaluate_over_domain(&domain)
+    );
+}
@@ -0,0 +1,251 @@
+//! Constant-time modular inversion with the Bernstein-Yang "safegcd"
+//! algorithm, following the signed 62-bit limb formulation of
+//! libsecp256k1's `modinv64`.
+//!
+//! The inverse is found by running a fixed number of "divsteps" on the
+//! pair `(f, g) = (m, x)` while tracking `d, e` with `f = d * x` and
+//! `g = e * x` (mod m). Divsteps are batched 62 at a time into a 2x2
+//! transition matrix computed from the low bits alone, which is then applied
+//! to the full values. Once `g` reaches zero, `f = ±1` and `±d` is the
+//! inverse.
+//!
+//! See <https://gcd.cr.yp.to/safegcd-20190413.pdf>.
+
+/// The low 62 bits of a limb.
+const M62: u64 = u64::MAX >> 2;
+
+/// A modulus prepared for inversion, held in `L` signed 62-bit limbs.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct SafeGcd<const L: usize> {
+    modulus: [i64; L],
+    /// m^-1 mod 2^62
+    modulus_inv62: u64,
+    /// The number of batches of 62 divsteps that are always sufficient.
+    batches: usize,
+}
+
+impl<const L: usize> SafeGcd<L> {
+    /// Prepares the odd `modulus` of at most `bits` bits.
+    pub(crate) const fn new<const W: usize>(modulus: &[u64; W], bits: usize) -> Self {
+        assert!(L * 62 > bits && W * 64 >= bits && modulus[0] & 1 == 1);
+
+        // Newton iteration, each step doubling the number of correct bits
+        // starting from m * m = 1 (mod 8)
+        let mut inv = modulus[0];
+        let mut i = 0;
+        while i < 5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus[0].wrapping_mul(inv)));
+            i += 1;
+        }
+
+        // Theorem 11.2 of the paper bounds the divsteps needed for inputs
+        // of d >= 46 bits by (49 * d + 57) / 17.
+        let divsteps = (49 * bits + 57) / 17;
+
+        Self {
+            modulus: to_signed62(modulus),
+            modulus_inv62: inv & M62,
+            batches: divsteps.div_ceil(62),
+        }
+    }
+
+    /// Computes x^-1 mod m in constant time, for a canonical `x < m`. Zero
+    /// is mapped to zero.
+    pub(crate) fn invert<const W: usize>(&self, x: &[u64; W]) -> [u64; W] {
+        let mut f = self.modulus;
+        let mut g = to_signed62::<W, L>(x);
+        let mut d = [0i64; L];
+        let mut e = [0i64; L];
+        e[0] = 1;
+        let mut delta = 1;
+
+        for _ in 0..self.batches {
+            let t;
+            (delta, t) = divsteps_62(delta, f[0] as u64, g[0] as u64);
+            self.update_de(&mut d, &mut e, &t);
+            update_fg(&mut f, &mut g, &t);
+        }
+
+        // g is now zero, and f is 1 or -1 unless x was zero
+        self.normalize(&mut d, f[L - 1] >> 63);
+        from_signed62(&d)
+    }
+
+    /// Applies the transition matrix to `d, e`, dividing by 2^62 modulo m.
+    /// Both must lie in (-2m, m), which is preserved.
+    fn update_de(&self, d: &mut [i64; L], e: &mut [i64; L], t: &[i64; 4]) {
+        let [u, v, q, r] = *t;
+        let m = &self.modulus;
+
+        // Start with [u, q] if d is negative and [v, r] if e is negative, so
+        // that adding multiples of m keeps the results in range.
+        let sd = d[L - 1] >> 63;
+        let se = e[L - 1] >> 63;
+        let mut md = (u & sd) + (v & se);
+        let mut me = (q & sd) + (r & se);
+
+        let mut cd = u as i128 * d[0] as i128 + v as i128 * e[0] as i128;
+        let mut ce = q as i128 * d[0] as i128 + r as i128 * e[0] as i128;
+
+        // Choose md, me such that t * [d, e] + m * [md, me] has its low 62
+        // bits zero.
+        md -= (self
+            .modulus_inv62
+            .wrapping_mul(cd as u64)
+            .wrapping_add(md as u64)
+            & M62) as i64;
+        me -= (self
+            .modulus_inv62
+            .wrapping_mul(ce as u64)
+            .wrapping_add(me as u64)
+            & M62) as i64;
+
+        cd += m[0] as i128 * md as i128;
+        ce += m[0] as i128 * me as i128;
+        debug_assert_eq!(cd as u64 & M62, 0);
+        debug_assert_eq!(ce as u64 & M62, 0);
+        cd >>= 62;
+        ce >>= 62;
+
+        for i in 1..L {
+            cd += u as i128 * d[i] as i128 + v as i128 * e[i] as i128 + m[i] as i128 * md as i128;
+            ce += q as i128 * d[i] as i128 + r as i128 * e[i] as i128 + m[i] as i128 * me as i128;
+            d[i - 1] = (cd as u64 & M62) as i64;
+            e[i - 1] = (ce as u64 & M62) as i64;
+            cd >>= 62;
+            ce >>= 62;
+        }
+        d[L - 1] = cd as i64;
+        e[L - 1] = ce as i64;
+    }
+
+    /// Brings `r` from (-2m, m) to [0, m), negating it first if `sign` is
+    /// all ones.
+    fn normalize(&self, r: &mut [i64; L], sign: i64) {
+        let m = &self.modulus;
+
+        let cond_add = r[L - 1] >> 63;
+        for i in 0..L {
+            r[i] += m[i] & cond_add;
+            r[i] = (r[i] ^ sign) - sign;
+        }
+        propagate(r);
+
+        let cond_add = r[L - 1] >> 63;
+        for i in 0..L {
+            r[i] += m[i] & cond_add;
+        }
+        propagate(r);
+    }
+}
+
+/// Carries the excess of every limb but the last into the next one.
+fn propagate<const L: usize>(r: &mut [i64; L]) {
+    for i in 0..L - 1 {
+        r[i + 1] += r[i] >> 62;
+        r[i] &= M62 as i64;
+    }
+}
+
+/// Runs 62 divsteps on the low bits of `f` and `g`, returning the new
+/// `delta` and the transition matrix `[u, v, q, r]` scaled by 2^62, so that
+/// 2^62 * (f', g') = (u * f + v * g, q * f + r * g).
+fn divsteps_62(mut delta: i64, mut f: u64, mut g: u64) -> (i64, [i64; 4]) {
+    let (mut u, mut v, mut q, mut r) = (1i64, 0i64, 0i64, 1i64);
+
+    for _ in 0..62 {
+        debug_assert_eq!(f & 1, 1);
+        // All ones when g is odd, and when additionally delta > 0
+        let g_odd = (g & 1).wrapping_neg();
+        let swap = ((delta.wrapping_neg() >> 63) as u64) & g_odd;
+
+        // If swapping, (delta, f, g) = (-delta, g, -f) and likewise for the
+        // matrix rows
+        let swap_i = swap as i64;
+        delta = (delta ^ swap_i) - swap_i;
+        let t = (f ^ g) & swap;
+        f ^= t;
+        g ^= t;
+        g = (g ^ swap).wrapping_sub(swap);
+        let t = (u ^ q) & swap_i;
+        u ^= t;
+        q ^= t;
+        let t = (v ^ r) & swap_i;
+        v ^= t;
+        r ^= t;
+        q = (q ^ swap_i) - swap_i;
+        r = (r ^ swap_i) - swap_i;
+
+        // g is now even after adding f if it was odd, and is halved
+        let g_odd_i = g_odd as i64;
+        g = g.wrapping_add(f & g_odd);
+        q += u & g_odd_i;
+        r += v & g_odd_i;
+        g >>= 1;
+        u <<= 1;
+        v <<= 1;
+        delta += 1;
+    }
+
+    (delta, [u, v, q, r])
+}
+
+/// Applies the transition matrix to `f, g`, whose division by 2^62 is
+/// exact.
+fn update_fg<const L: usize>(f: &mut [i64; L], g: &mut [i64; L], t: &[i64; 4]) {
+    let [u, v, q, r] = t.map(i128::from);
+
+    let mut cf = u * f[0] as i128 + v * g[0] as i128;
+    let mut cg = q * f[0] as i128 + r * g[0] as i128;
+    debug_assert_eq!(cf as u64 & M62, 0);
+    debug_assert_eq!(cg as u64 & M62, 0);
+    cf >>= 62;
+    cg >>= 62;
+
+    for i in 1..L {
+        cf += u * f[i] as i128 + v * g[i] as i128;
+        cg += q * f[i] as i128 + r * g[i] as i128;
+        f[i - 1] = (cf as u64 & M62) as i64;
+        g[i - 1] = (cg as u64 & M62) as i64;
+        cf >>= 62;
+        cg >>= 62;
+    }
+    f[L - 1] = cf as i64;
+    g[L - 1] = cg as i64;
+}
+
+/// Splits little-endian 64-bit limbs into 62-bit limbs.
+const fn to_signed62<const W: usize, const L: usize>(x: &[u64; W]) -> [i64; L] {
+    let mut out = [0i64; L];
+    let mut i = 0;
+    while i < L {
+        let limb = i * 62 / 64;
+        let shift = i * 62 % 64;
+        let mut v = 0;
+        if limb < W {
+            v = x[limb] >> shift;
+        }
+        if shift > 2 && limb + 1 < W {
+            v |= x[limb + 1] << (64 - shift);
+        }
+        out[i] = (v & M62) as i64;
+        i += 1;
+    }
+    out
+}
+
+/// Joins non-negative 62-bit limbs into little-endian 64-bit limbs.
+fn from_signed62<const L: usize, const W: usize>(x: &[i64; L]) -> [u64; W] {
+    let mut out = [0u64; W];
+    for (i, &v) in x.iter().enumerate() {
+        let limb = i * 62 / 64;
+        let shift = i * 62 % 64;
+        if limb < W {
+            out[limb] |= (v as u64) << shift;
+        }
+        if shift > 2 && limb + 1 < W {
+            out[limb + 1] |= (v as u64) >> (64 - shift);
+        }
+    }
+    out
+}
@@ -4,10 +4,11 @@
 use core::convert::TryFrom;
 use core::fmt::{self, Formatter};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::str::FromStr;
 use rand_core::RngCore;
 
 use ff::{Field, PrimeField};
-use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq, CtOption};
 
 #[cfg(feature = "bits")]
 use core::convert::TryInto;
@@ -24,7 +25,10 @@ use crate::Bls12381G1;
 #[cfg(feature = "bits")]
 use ff::{FieldBits, PrimeFieldBits};
 
+use crate::limb32;
+use crate::safegcd::SafeGcd;
 use crate::util::{adc, decode_hex_into_slice, mac, sbb};
+use crate::DecodingError;
 
 /// Represents an element of the scalar field $\mathbb{F}_q$ of the BLS12-381 elliptic
 /// curve construction.
@@ -40,9 +44,73 @@ impl fmt::Debug for Scalar {
     }
 }
 
+/// Formats the scalar as 64 big endian hex digits, or as a decimal integer
+/// with the alternate flag, `{:#}`, which also honors width, fill and
+/// alignment.
+///
+/// The hex form has no `0x` prefix, so [`FromStr`] rejects it: use `{:#}`, or
+/// prepend `0x`, for strings that must parse.
 impl fmt::Display for Scalar {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{:x}", self)
+        if !f.alternate() {
+            return write!(f, "{:x}", self);
+        }
+
+        // 2^255 has 77 decimal digits
+        let mut digits = [0u8; 77];
+        let mut start = digits.len();
+        let mut limbs = self.to_raw();
+        loop {
+            let mut rem = 0u64;
+            for limb in limbs.iter_mut().rev() {
+                let cur = ((rem as u128) << 64) | *limb as u128;
+                *limb = (cur / 10) as u64;
+                rem = (cur % 10) as u64;
+            }
+            start -= 1;
+            digits[start] = b'0' + rem as u8;
+            if limbs == [0; 4] {
+                break;
+            }
+        }
+        f.pad(core::str::from_utf8(&digits[start..]).unwrap())
+    }
+}
+
+/// Parses a decimal integer, or a hex integer prefixed with `0x`, which must
+/// be less than the modulus.
+///
+/// Decimal integers may not have leading zeros. The default hex output of
+/// `Display` is unprefixed and zero padded to 64 digits, so it is rejected
+/// rather than read as a different decimal value.
+impl FromStr for Scalar {
+    type Err = DecodingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => (hex, 16),
+            None => (s, 10),
+        };
+        if digits.is_empty() || (radix == 10 && digits.len() > 1 && digits.starts_with('0')) {
+            return Err(DecodingError::InvalidDigit);
+        }
+
+        let mut limbs = [0u64; 4];
+        for c in digits.chars() {
+            let mut carry = c.to_digit(radix).ok_or(DecodingError::InvalidDigit)? as u64;
+            for limb in limbs.iter_mut() {
+                (*limb, carry) = mac(carry, *limb, radix as u64, 0);
+            }
+            if carry != 0 {
+                return Err(DecodingError::NonCanonicalField);
+            }
+        }
+
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        Option::from(Scalar::from_le_bytes(&bytes)).ok_or(DecodingError::NonCanonicalField)
     }
 }
 
@@ -58,6 +126,30 @@ impl From<u64> for Scalar {
     }
 }
 
+impl From<u128> for Scalar {
+    fn from(val: u128) -> Scalar {
+        Scalar([val as u64, (val >> 64) as u64, 0, 0]) * R2
+    }
+}
+
+/// Negative values are mapped to their residue modulo q.
+impl From<i64> for Scalar {
+    fn from(val: i64) -> Scalar {
+        let mut s = Scalar::from(val.unsigned_abs());
+        s.conditional_negate(Choice::from((val < 0) as u8));
+        s
+    }
+}
+
+/// Negative values are mapped to their residue modulo q.
+impl From<i128> for Scalar {
+    fn from(val: i128) -> Scalar {
+        let mut s = Scalar::from(val.unsigned_abs());
+        s.conditional_negate(Choice::from((val < 0) as u8));
+        s
+    }
+}
+
 impl ConstantTimeEq for Scalar {
     fn ct_eq(&self, other: &Self) -> Choice {
         self.0[0].ct_eq(&other.0[0])
@@ -105,7 +197,6 @@ const MODULUS: Scalar = Scalar([
 ]);
 
 /// The modulus as u32 limbs.
-#[cfg(all(feature = "bits", not(target_pointer_width = "64")))]
 const MODULUS_LIMBS_32: [u32; 8] = [
     0x0000_0001,
     0xffff_ffff,
@@ -176,6 +267,13 @@ impl<'a, 'b> Mul<&'b Scalar> for &'a Scalar {
 impl_binops_additive!(Scalar, Scalar);
 impl_binops_multiplicative!(Scalar, Scalar);
 
+/// The modulus followed by INV, as used by the assembly backend
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+const MODULUS_AND_INV: [u64; 5] = [MODULUS.0[0], MODULUS.0[1], MODULUS.0[2], MODULUS.0[3], INV];
+
+/// The modulus prepared for safegcd inversion
+const SAFEGCD: SafeGcd<5> = SafeGcd::new(&MODULUS.0, 255);
+
 /// INV = -(q^{-1} mod 2^64) mod 2^64
 const INV: u64 = 0xffff_fffe_ffff_ffff;
 
@@ -245,6 +343,24 @@ const DELTA: Scalar = Scalar([
     0x6185_d066_27c0_67cb,
 ]);
 
+/// `ROOTS_OF_UNITY[k]` is a primitive 2^k-th root of unity, obtained by
+/// squaring `ROOT_OF_UNITY` S - k times.
+const ROOTS_OF_UNITY: [Scalar; S as usize + 1] = square_repeatedly(ROOT_OF_UNITY);
+
+/// `ROOTS_OF_UNITY_INV[k]` is the inverse of `ROOTS_OF_UNITY[k]`.
+const ROOTS_OF_UNITY_INV: [Scalar; S as usize + 1] = square_repeatedly(ROOT_OF_UNITY_INV);
+
+/// Returns `[x^(2^S), ..., x^2, x]`.
+const fn square_repeatedly(x: Scalar) -> [Scalar; S as usize + 1] {
+    let mut powers = [x; S as usize + 1];
+    let mut k = S as usize;
+    while k > 0 {
+        powers[k - 1] = powers[k].square_portable();
+        k -= 1;
+    }
+    powers
+}
+
 impl Default for Scalar {
     #[inline]
     fn default() -> Self {
@@ -395,6 +511,43 @@ impl Scalar {
         ])
     }
 
+    /// Interprets `bytes` as a big endian integer of any length and reduces
+    /// it modulo q.
+    pub fn from_bytes_mod_order(bytes: &[u8]) -> Scalar {
+        Self::from_digits_mod_order(bytes.rchunks(32).rev().map(|chunk| {
+            let mut digit = [0u8; 32];
+            digit[32 - chunk.len()..].copy_from_slice(chunk);
+            digit.reverse();
+            digit
+        }))
+    }
+
+    /// Interprets `bytes` as a little endian integer of any length and
+    /// reduces it modulo q.
+    pub fn from_le_bytes_mod_order(bytes: &[u8]) -> Scalar {
+        Self::from_digits_mod_order(bytes.chunks(32).rev().map(|chunk| {
+            let mut digit = [0u8; 32];
+            digit[..chunk.len()].copy_from_slice(chunk);
+            digit
+        }))
+    }
+
+    /// Evaluates little endian 256-bit digits, most significant first, with
+    /// Horner's rule.
+    fn from_digits_mod_order(digits: impl Iterator<Item = [u8; 32]>) -> Scalar {
+        // `R2` is the Montgomery form of 2^256, and as in `from_u512`, any
+        // 256-bit digit is brought into Montgomery form by multiplying by it.
+        digits.fold(Scalar::ZERO, |acc, digit| {
+            let digit = Scalar([
+                u64::from_le_bytes(<[u8; 8]>::try_from(&digit[0..8]).unwrap()),
+                u64::from_le_bytes(<[u8; 8]>::try_from(&digit[8..16]).unwrap()),
+                u64::from_le_bytes(<[u8; 8]>::try_from(&digit[16..24]).unwrap()),
+                u64::from_le_bytes(<[u8; 8]>::try_from(&digit[24..32]).unwrap()),
+            ]);
+            acc * R2 + digit * R2
+        })
+    }
+
     /// Read from output of a KDF
     pub fn from_okm(bytes: &[u8; 48]) -> Scalar {
         const F_2_192: Scalar = Scalar([
@@ -447,7 +600,7 @@ impl Scalar {
     /// Converts from an integer represented in little endian
     /// into its (congruent) `Scalar` representation.
     pub const fn from_raw_unchecked(val: [u64; 4]) -> Self {
-        (&Scalar(val)).mul(&R2)
+        (&Scalar(val)).mul_portable(&R2)
     }
 
     /// Converts this `Scalar` into an integer represented in little endian
@@ -457,8 +610,38 @@ impl Scalar {
     }
 
     /// Squares this element.
+    #[cfg(not(all(feature = "asm", target_arch = "x86_64")))]
     #[inline]
     pub const fn square(&self) -> Scalar {
+        self.square_portable()
+    }
+
+    /// Squares this element, using MULX and ADX when the CPU supports them.
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    #[inline]
+    pub fn square(&self) -> Scalar {
+        if crate::asm::has_mulx_adx() {
+            // SAFETY: the CPU supports BMI2 and ADX
+            let t = unsafe { crate::asm::montgomery_mul_4(&self.0, &self.0, &MODULUS_AND_INV) };
+            Scalar::sub(&Scalar(t), &MODULUS)
+        } else {
+            self.square_portable()
+        }
+    }
+
+    /// Squares this element without assembly, also in const contexts.
+    #[inline]
+    pub(crate) const fn square_portable(&self) -> Scalar {
+        if limb32::ENABLED {
+            self.mul_u32(self)
+        } else {
+            self.square_u64()
+        }
+    }
+
+    /// Squares this element with 64-bit limbs.
+    #[inline]
+    const fn square_u64(&self) -> Scalar {
         let (r1, carry) = mac(0, self.0[0], self.0[1], 0);
         let (r2, carry) = mac(0, self.0[0], self.0[2], carry);
         let (r3, r4) = mac(0, self.0[0], self.0[3], carry);
@@ -523,9 +706,189 @@ impl Scalar {
         res
     }
 
+    /// Exponentiates `self` by a 64-bit exponent.
+    pub fn pow_u64(&self, by: u64) -> Self {
+        let mut res = Self::ONE;
+        for i in (0..64).rev() {
+            res = res.square();
+            let mut tmp = res;
+            tmp *= self;
+            res.conditional_assign(&tmp, (((by >> i) & 0x1) as u8).into());
+        }
+        res
+    }
+
+    /// Exponentiates `self` by a 64-bit exponent.
+    ///
+    /// **This operation is variable time with respect
+    /// to the exponent.** If the exponent is fixed,
+    /// this operation is effectively constant time.
+    pub fn pow_u64_vartime(&self, by: u64) -> Self {
+        let mut res = Self::ONE;
+        for i in (0..64 - by.leading_zeros()).rev() {
+            res = res.square();
+
+            if ((by >> i) & 1) == 1 {
+                res.mul_assign(self);
+            }
+        }
+        res
+    }
+
+    /// Returns a primitive 2^`log_n`-th root of unity, which generates the
+    /// multiplicative subgroup of order 2^`log_n`, or `None` if `log_n`
+    /// exceeds the two-adicity `S = 32` of the field.
+    ///
+    /// The roots are consistent across sizes: squaring the root for
+    /// `log_n` gives the root for `log_n - 1`.
+    pub fn root_of_unity(log_n: u32) -> Option<Self> {
+        ROOTS_OF_UNITY.get(log_n as usize).copied()
+    }
+
+    /// Returns the inverse of [`Scalar::root_of_unity`].
+    pub fn root_of_unity_inv(log_n: u32) -> Option<Self> {
+        ROOTS_OF_UNITY_INV.get(log_n as usize).copied()
+    }
+
+    /// Returns the shift `g^index` of a coset of the multiplicative subgroups
+    /// of power of two order, where `g = 7` is the multiplicative generator.
+    ///
+    /// For every subgroup `H` of order up to 2^32, the cosets `g^i H` for
+    /// `0 <= i < 2^32` are pairwise disjoint, and index 0 is `H` itself.
+    pub fn coset_generator(index: u32) -> Self {
+        GENERATOR.pow_u64_vartime(index as u64)
+    }
+
+    /// Raises this element to the power (t - 1) / 2, where t is the odd part
+    /// of q - 1, using a fixed addition chain. This is the exponentiation
+    /// step of the Tonelli-Shanks square root.
+    fn pow_t_minus_1_div_2(&self) -> Self {
+        #[inline(always)]
+        fn square_assign_multi(n: &mut Scalar, num_times: usize) {
+            for _ in 0..num_times {
+                *n = n.square();
+            }
+        }
+        // (t - 1) // 2 = 6104339283789297388802252303364915521546564123189034618274734669823
+        // sliding window of width 4 over the exponent bits
+        let x1 = *self;
+        let x2 = x1.square();
+        let x3 = x1 * x2;
+        let x5 = x3 * x2;
+        let x7 = x5 * x2;
+        let x9 = x7 * x2;
+        let x11 = x9 * x2;
+        let x13 = x11 * x2;
+        let x15 = x13 * x2;
+        let mut t = x7;
+        square_assign_multi(&mut t, 6);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x11;
+        square_assign_multi(&mut t, 5);
+        t *= &x13;
+        square_assign_multi(&mut t, 5);
+        t *= &x7;
+        square_assign_multi(&mut t, 4);
+        t *= &x5;
+        square_assign_multi(&mut t, 4);
+        t *= &x3;
+        square_assign_multi(&mut t, 5);
+        t *= &x5;
+        square_assign_multi(&mut t, 4);
+        t *= &x3;
+        square_assign_multi(&mut t, 5);
+        t *= &x7;
+        square_assign_multi(&mut t, 5);
+        t *= &x11;
+        square_assign_multi(&mut t, 3);
+        t *= &x7;
+        square_assign_multi(&mut t, 4);
+        t *= &x5;
+        square_assign_multi(&mut t, 3);
+        t *= &x1;
+        square_assign_multi(&mut t, 7);
+        t *= &x3;
+        square_assign_multi(&mut t, 4);
+        t *= &x3;
+        square_assign_multi(&mut t, 5);
+        t *= &x7;
+        square_assign_multi(&mut t, 5);
+        t *= &x7;
+        square_assign_multi(&mut t, 3);
+        t *= &x3;
+        square_assign_multi(&mut t, 8);
+        t *= &x1;
+        square_assign_multi(&mut t, 11);
+        t *= &x9;
+        square_assign_multi(&mut t, 3);
+        t *= &x5;
+        square_assign_multi(&mut t, 7);
+        t *= &x7;
+        square_assign_multi(&mut t, 3);
+        t *= &x3;
+        square_assign_multi(&mut t, 11);
+        t *= &x5;
+        square_assign_multi(&mut t, 4);
+        t *= &x5;
+        square_assign_multi(&mut t, 5);
+        t *= &x7;
+        square_assign_multi(&mut t, 5);
+        t *= &x15;
+        square_assign_multi(&mut t, 5);
+        t *= &x13;
+        square_assign_multi(&mut t, 3);
+        t *= &x1;
+        square_assign_multi(&mut t, 12);
+        t *= &x11;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x9;
+        square_assign_multi(&mut t, 5);
+        t *= &x13;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 5);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 4);
+        t *= &x15;
+        square_assign_multi(&mut t, 3);
+        t *= &x7;
+        t
+    }
+
     /// Computes the multiplicative inverse of this element,
     /// failing if the element is zero.
     pub fn invert(&self) -> CtOption<Self> {
+        // safegcd inverts the Montgomery form a * R to a^-1 * R^-1, and
+        // multiplying by R^3 yields a^-1 * R
+        let t = Scalar(SAFEGCD.invert(&self.0)) * R3;
+
+        CtOption::new(t, !self.is_zero())
+    }
+
+    /// Computes the multiplicative inverse of this element by
+    /// exponentiation, failing if the element is zero. This is slower than
+    /// [`invert`](Self::invert) and is kept as a fallback and reference
+    /// implementation.
+    pub fn invert_fermat(&self) -> CtOption<Self> {
         #[inline(always)]
         fn square_assign_multi(n: &mut Scalar, num_times: usize) {
             for _ in 0..num_times {
@@ -622,6 +985,42 @@ impl Scalar {
         CtOption::new(t0, !self.ct_eq(&Self::ZERO))
     }
 
+    /// Inverts every element of `values` in place, leaving zeros unchanged.
+    /// This uses Montgomery's trick to share one inversion between all of the
+    /// elements, allocating space for their running products. Without the
+    /// `alloc` feature this uses one inversion per small chunk instead.
+    pub fn batch_invert(values: &mut [Scalar]) {
+        crate::util::batch_invert(values)
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged.
+    /// This is faster than [`batch_invert`](Self::batch_invert) but variable
+    /// time with respect to which elements are zero.
+    pub fn batch_invert_vartime(values: &mut [Scalar]) {
+        crate::util::batch_invert_vartime(values)
+    }
+
+    /// Inverts every element of `values` in place, leaving zeros unchanged,
+    /// using `scratch` for the running products so that all of the elements
+    /// share one inversion without allocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scratch` is shorter than `values`.
+    pub fn batch_invert_with_scratch(values: &mut [Scalar], scratch: &mut [Scalar]) {
+        crate::util::batch_invert_with_scratch(values, scratch)
+    }
+
+    /// The same as [`batch_invert_with_scratch`](Self::batch_invert_with_scratch)
+    /// but variable time with respect to which elements are zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scratch` is shorter than `values`.
+    pub fn batch_invert_vartime_with_scratch(values: &mut [Scalar], scratch: &mut [Scalar]) {
+        crate::util::batch_invert_vartime_with_scratch(values, scratch)
+    }
+
     #[inline(always)]
     pub(crate) const fn montgomery_reduce(
         r0: u64,
@@ -670,8 +1069,52 @@ impl Scalar {
     }
 
     /// Multiplies `rhs` by `self`, returning the result.
+    #[cfg(not(all(feature = "asm", target_arch = "x86_64")))]
     #[inline]
     pub const fn mul(&self, rhs: &Self) -> Self {
+        self.mul_portable(rhs)
+    }
+
+    /// Multiplies `rhs` by `self`, returning the result. This uses MULX and
+    /// ADX when the CPU supports them.
+    #[cfg(all(feature = "asm", target_arch = "x86_64"))]
+    #[inline]
+    pub fn mul(&self, rhs: &Self) -> Self {
+        if crate::asm::has_mulx_adx() {
+            // SAFETY: the CPU supports BMI2 and ADX. Only `rhs` needs to be
+            // reduced, so `self` may be a wide integer being converted.
+            let t = unsafe { crate::asm::montgomery_mul_4(&rhs.0, &self.0, &MODULUS_AND_INV) };
+            Scalar::sub(&Scalar(t), &MODULUS)
+        } else {
+            self.mul_portable(rhs)
+        }
+    }
+
+    /// Multiplies `rhs` by `self` without assembly, also in const contexts.
+    #[inline]
+    pub(crate) const fn mul_portable(&self, rhs: &Self) -> Self {
+        if limb32::ENABLED {
+            self.mul_u32(rhs)
+        } else {
+            self.mul_u64(rhs)
+        }
+    }
+
+    /// Multiplies `rhs` by `self` with 32-bit limbs.
+    #[inline]
+    const fn mul_u32(&self, rhs: &Self) -> Self {
+        let t = limb32::sum_of_products(
+            &[limb32::to_u32(&self.0)],
+            &[limb32::to_u32(&rhs.0)],
+            &MODULUS_LIMBS_32,
+            INV as u32,
+        );
+        Scalar::sub(&Scalar(limb32::to_u64(&t)), &MODULUS)
+    }
+
+    /// Multiplies `rhs` by `self` with 64-bit limbs.
+    #[inline]
+    const fn mul_u64(&self, rhs: &Self) -> Self {
         // Schoolbook multiplication
 
         let (r0, carry) = mac(0, self.0[0], rhs.0[0], 0);
@@ -802,16 +1245,7 @@ impl Field for Scalar {
     }
 
     fn sqrt(&self) -> CtOption<Self> {
-        // (t - 1) // 2 = 6104339283789297388802252303364915521546564123189034618274734669823
-        ff::helpers::sqrt_tonelli_shanks(
-            self,
-            [
-                0x7fff_2dff_7fff_ffff,
-                0x04d0_ec02_a9de_d201,
-                0x94ce_bea4_199c_ec04,
-                0x0000_0000_39f6_d3a9,
-            ],
-        )
+        crate::util::sqrt_tonelli_shanks_from_w(self, self.pow_t_minus_1_div_2(), S, ROOT_OF_UNITY)
     }
 
     fn is_zero_vartime(&self) -> bool {
@@ -1090,7 +1524,7 @@ impl From<GenericArray<u8, U32>> for Scalar {
 
 impl From<Scalar> for GenericArray<u8, U32> {
     fn from(value: Scalar) -> Self {
-        GenericArray::clone_from_slice(&value.to_be_bytes())
+        value.to_be_bytes().into()
     }
 }
 
@@ -1251,16 +1685,14 @@ impl FromUintUnchecked for Scalar {
     }
 }
 
-impl_from_bytes!(Scalar, |s: &Scalar| s.to_be_bytes(), |arr: &[u8]| {
-    let tmp: [u8; 32] = arr.try_into().map_err(|_| {
-        alloc::format!(
-            "Invalid number of bytes for Scalar, expected {}, found {}",
-            Scalar::BYTES,
-            arr.len()
-        )
-    })?;
-    Ok::<CtOption<Scalar>, alloc::string::String>(Scalar::from_be_bytes(&tmp))
-});
+impl_from_bytes!(
+    Scalar,
+    |s: &Scalar| s.to_be_bytes(),
+    |arr: &[u8]| -> Result<Scalar, DecodingError> {
+        Option::from(Scalar::from_be_bytes(&DecodingError::to_array(arr)?))
+            .ok_or(DecodingError::NonCanonicalField)
+    }
+);
 
 impl Invert for Scalar {
     type Output = CtOption<Self>;
@@ -1867,6 +2299,86 @@ fn test_invert_is_pow() {
     }
 }
 
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+#[test]
+fn test_asm_mul() {
+    use rand_core::SeedableRng;
+
+    if !crate::asm::has_mulx_adx() {
+        return;
+    }
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let mut values = std::vec![Scalar::ZERO, Scalar::ONE, -Scalar::ONE, R2, R3];
+    values.extend((0..50).map(|_| Scalar::random(&mut rng)));
+    for a in &values {
+        assert_eq!(a.square(), a.square_portable());
+        for b in &values {
+            assert_eq!(a.mul(b), a.mul_portable(b));
+        }
+        // the left operand may be an unreduced integer
+        let wide = Scalar([u64::MAX; 4]);
+        assert_eq!(wide.mul(a), wide.mul_portable(a));
+    }
+}
+
+#[test]
+fn test_mul_u32() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let mut values = std::vec![Scalar::ZERO, Scalar::ONE, -Scalar::ONE, R2, R3];
+    values.extend((0..50).map(|_| Scalar::random(&mut rng)));
+    for a in &values {
+        assert_eq!(a.mul_u32(a), a.square_u64());
+        for b in &values {
+            assert_eq!(a.mul_u32(b), a.mul_u64(b));
+        }
+        // the left operand may be an unreduced integer
+        let wide = Scalar([u64::MAX; 4]);
+        assert_eq!(wide.mul_u32(a), wide.mul_u64(a));
+    }
+}
+
+#[test]
+fn test_invert_safegcd() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    assert!(bool::from(Scalar::ZERO.invert().is_none()));
+    assert_eq!(Scalar::ONE.invert().unwrap(), Scalar::ONE);
+    assert_eq!((-Scalar::ONE).invert().unwrap(), -Scalar::ONE);
+    // the largest Montgomery representation, q - 1
+    let max = Scalar([
+        0xffff_ffff_0000_0000,
+        0x53bd_a402_fffe_5bfe,
+        0x3339_d808_09a1_d805,
+        0x73ed_a753_299d_7d48,
+    ]);
+    assert_eq!(max.invert().unwrap(), max.invert_fermat().unwrap());
+    for _ in 0..100 {
+        let a = Scalar::random(&mut rng);
+        assert_eq!(a.invert().unwrap(), a.invert_fermat().unwrap());
+    }
+}
+
+#[test]
+fn test_addition_chain() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    // (t - 1) / 2
+    let exp = [
+        0x7fff_2dff_7fff_ffff,
+        0x04d0_ec02_a9de_d201,
+        0x94ce_bea4_199c_ec04,
+        0x0000_0000_39f6_d3a9,
+    ];
+    for _ in 0..20 {
+        let a = Scalar::random(&mut rng);
+        assert_eq!(a.pow_t_minus_1_div_2(), a.pow_vartime(&exp));
+    }
+}
+
 #[test]
 fn test_sqrt() {
     {
@@ -2042,13 +2554,203 @@ fn test_from_vec() {
     let mut bytes = alloc::vec![0u8; 32];
     for _ in 0..50 {
         rng.fill_bytes(&mut bytes[1..]);
-        let res: Result<Scalar, alloc::string::String> = (&bytes).try_into();
+        let res: Result<Scalar, DecodingError> = (&bytes).try_into();
         assert!(res.is_ok());
-        let res: Result<Scalar, alloc::string::String> = bytes.as_slice().try_into();
+        let res: Result<Scalar, DecodingError> = bytes.as_slice().try_into();
         assert!(res.is_ok());
-        let res: Result<Scalar, alloc::string::String> = bytes.clone().try_into();
+        let res: Result<Scalar, DecodingError> = bytes.clone().try_into();
         assert!(res.is_ok());
         let s = res.unwrap();
         assert_eq!(bytes, <&Scalar as Into<alloc::vec::Vec<u8>>>::into(&s));
     }
 }
+
+#[test]
+fn test_batch_invert() {
+    // The algorithm is tested in util, this only checks the wrapper
+    let two = Scalar::ONE + Scalar::ONE;
+    let mut values = [Scalar::ZERO, two];
+    Scalar::batch_invert_with_scratch(&mut values, &mut [Scalar::ZERO; 2]);
+    assert_eq!(values, [Scalar::ZERO, two.invert().unwrap()]);
+}
+
+#[test]
+fn test_from_integers() {
+    assert_eq!(
+        Scalar::from(u128::MAX),
+        Scalar::from(u64::MAX) * Scalar::from(u64::MAX) + Scalar::from(u64::MAX).double()
+    );
+    assert_eq!(
+        Scalar::from(7u128 << 64),
+        Scalar::from(7u64) * Scalar::from(1u128 << 64)
+    );
+    assert_eq!(Scalar::from(-1i64), -Scalar::ONE);
+    assert_eq!(Scalar::from(i64::MIN), -Scalar::from(1u64 << 63));
+    assert_eq!(Scalar::from(12345i64), Scalar::from(12345u64));
+    assert_eq!(Scalar::from(-12345i128), -Scalar::from(12345u64));
+    assert_eq!(Scalar::from(i128::MIN), -Scalar::from(1u128 << 127));
+    assert_eq!(Scalar::from(i128::MAX), Scalar::from(i128::MAX as u128));
+}
+
+#[test]
+fn test_from_str() {
+    assert_eq!("0".parse::<Scalar>(), Ok(Scalar::ZERO));
+    assert_eq!("0x0".parse::<Scalar>(), Ok(Scalar::ZERO));
+    assert_eq!("12345".parse::<Scalar>(), Ok(Scalar::from(12345u64)));
+    assert_eq!("0x3039".parse::<Scalar>(), Ok(Scalar::from(12345u64)));
+    assert_eq!(
+        "0X00000000000000003039".parse::<Scalar>(),
+        Ok(Scalar::from(12345u64))
+    );
+    assert_eq!(
+        "52435875175126190479447740508185965837690552500527637822603658699938581184512"
+            .parse::<Scalar>(),
+        Ok(-Scalar::ONE)
+    );
+    assert_eq!(
+        "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000".parse::<Scalar>(),
+        Ok(-Scalar::ONE)
+    );
+
+    // The modulus and values beyond 2^256
+    assert_eq!(
+        "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+            .parse::<Scalar>(),
+        Err(DecodingError::NonCanonicalField)
+    );
+    assert_eq!(
+        "0x10000000000000000000000000000000000000000000000000000000000000000".parse::<Scalar>(),
+        Err(DecodingError::NonCanonicalField)
+    );
+    assert_eq!(
+        "1000000000000000000000000000000000000000000000000000000000000000000000000000000"
+            .parse::<Scalar>(),
+        Err(DecodingError::NonCanonicalField)
+    );
+
+    for s in ["", "0x", "-1", "12a", "0xg", " 1", "1_000", "00", "012345"] {
+        assert_eq!(s.parse::<Scalar>(), Err(DecodingError::InvalidDigit));
+    }
+}
+
+#[test]
+fn test_display_decimal() {
+    use rand_core::SeedableRng;
+    use std::string::ToString;
+
+    assert_eq!(std::format!("{:#}", Scalar::ZERO), "0");
+    assert_eq!(std::format!("{:#}", Scalar::from(12345u64)), "12345");
+    assert_eq!(
+        std::format!("{:#}", -Scalar::ONE),
+        "52435875175126190479447740508185965837690552500527637822603658699938581184512"
+    );
+    assert_eq!(
+        Scalar::from(12345u64).to_string(),
+        "0000000000000000000000000000000000000000000000000000000000003039"
+    );
+    assert_eq!(std::format!("{:>#8}", Scalar::from(12345u64)), "   12345");
+    assert_eq!(std::format!("{:*<#7}", Scalar::from(12u64)), "12*****");
+
+    // The hex form is rejected rather than read back as decimal without the
+    // prefix
+    for s in [Scalar::from(16u64), Scalar::from(12345u64)] {
+        assert_eq!(
+            s.to_string().parse::<Scalar>(),
+            Err(DecodingError::InvalidDigit)
+        );
+        assert_eq!(std::format!("0x{}", s).parse::<Scalar>(), Ok(s));
+        assert_eq!(std::format!("{:#}", s).parse::<Scalar>(), Ok(s));
+    }
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    for _ in 0..100 {
+        let s = Scalar::random(&mut rng);
+        assert_eq!(std::format!("{:#}", s).parse::<Scalar>(), Ok(s));
+        assert_eq!(std::format!("0x{}", s).parse::<Scalar>(), Ok(s));
+        assert!(s.to_string().parse::<Scalar>().is_err());
+    }
+}
+
+#[test]
+fn test_from_bytes_mod_order() {
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    let mut bytes = [0u8; 200];
+    rng.fill_bytes(&mut bytes);
+
+    for len in [0, 1, 16, 31, 32, 33, 63, 64, 65, 128, 200] {
+        let be = &bytes[..len];
+        let expected = be.iter().fold(Scalar::ZERO, |acc, b| {
+            acc * Scalar::from(256u64) + Scalar::from(*b as u64)
+        });
+        assert_eq!(Scalar::from_bytes_mod_order(be), expected);
+
+        let mut le = be.to_vec();
+        le.reverse();
+        assert_eq!(Scalar::from_le_bytes_mod_order(&le), expected);
+    }
+
+    let wide = <[u8; 64]>::try_from(&bytes[..64]).unwrap();
+    assert_eq!(
+        Scalar::from_le_bytes_mod_order(&wide),
+        Scalar::from_bytes_wide(&wide)
+    );
+
+    // The modulus itself, and the largest 256-bit integer
+    let mut q = [0u8; 32];
+    for (chunk, limb) in q.chunks_exact_mut(8).zip(MODULUS.0.iter().rev()) {
+        chunk.copy_from_slice(&limb.to_be_bytes());
+    }
+    assert_eq!(Scalar::from_bytes_mod_order(&q), Scalar::ZERO);
+    // R2 has the value 2^256 in Montgomery form
+    assert_eq!(Scalar::from_bytes_mod_order(&[0xff; 32]), R2 - Scalar::ONE);
+}
+
+#[test]
+fn test_roots_of_unity() {
+    assert_eq!(Scalar::root_of_unity(0), Some(Scalar::ONE));
+    assert_eq!(Scalar::root_of_unity(1), Some(-Scalar::ONE));
+    assert_eq!(Scalar::root_of_unity(S), Some(ROOT_OF_UNITY));
+    assert_eq!(Scalar::root_of_unity_inv(S), Some(ROOT_OF_UNITY_INV));
+    assert_eq!(Scalar::root_of_unity(S + 1), None);
+    assert_eq!(Scalar::root_of_unity_inv(S + 1), None);
+
+    for log_n in 1..=S {
+        let root = Scalar::root_of_unity(log_n).unwrap();
+        let half = root.pow_u64(1 << (log_n - 1));
+        assert_eq!(half, -Scalar::ONE);
+        assert_eq!(half.square(), Scalar::ONE);
+        assert_eq!(root.square(), Scalar::root_of_unity(log_n - 1).unwrap());
+        assert_eq!(
+            root * Scalar::root_of_unity_inv(log_n).unwrap(),
+            Scalar::ONE
+        );
+    }
+
+    // Cosets of the largest subgroup are disjoint
+    assert_eq!(Scalar::coset_generator(0), Scalar::ONE);
+    assert_eq!(Scalar::coset_generator(1), Scalar::MULTIPLICATIVE_GENERATOR);
+    for i in 1..8 {
+        let shift = Scalar::coset_generator(i);
+        assert_ne!(shift.pow_u64(1 << S), Scalar::ONE);
+        assert_eq!(
+            shift * Scalar::coset_generator(1),
+            Scalar::coset_generator(i + 1)
+        );
+    }
+    assert_ne!(
+        Scalar::coset_generator(u32::MAX).pow_u64(1 << S),
+        Scalar::ONE
+    );
+}
+
+#[test]
+fn test_pow_u64() {
+    let a = Scalar::from(0x1234_5678u64) + ROOT_OF_UNITY;
+    for e in [0, 1, 2, 3, 0xdead_beef, u64::MAX] {
+        let expected = a.pow(&[e, 0, 0, 0]);
+        assert_eq!(a.pow_u64(e), expected);
+        assert_eq!(a.pow_u64_vartime(e), expected);
+    }
+}
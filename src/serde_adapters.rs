@@ -0,0 +1,357 @@
+//! This module provides helper traits for serializing group elements in forms
+//! other than the default, for use with `#[serde(with = "...")]` in the same
+//! way as [`ScalarLe`](crate::ScalarLe).
+
+use serde::{Deserializer, Serialize, Serializer};
+use subtle::CtOption;
+
+use crate::util::{base64_len, decode_base64_into_slice, encode_base64_into_slice};
+use crate::{G1Affine, G1Projective, G2Affine, G2Projective};
+
+#[cfg(feature = "pairings")]
+use crate::Gt;
+
+/// The largest string produced, which is the hex encoding of a `Gt`.
+const MAX_STR_LEN: usize = 1152;
+
+/// How bytes are encoded as a string for human-readable formats.
+#[derive(Copy, Clone)]
+enum Encoding {
+    Hex,
+    Base64,
+}
+
+fn serialize_bytes<S: Serializer>(
+    bytes: &[u8],
+    encoding: Encoding,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    if s.is_human_readable() {
+        let mut buf = [0u8; MAX_STR_LEN];
+        let len = match encoding {
+            Encoding::Hex => {
+                let len = bytes.len() * 2;
+                hex::encode_to_slice(bytes, &mut buf[..len]).unwrap();
+                len
+            }
+            Encoding::Base64 => {
+                let len = base64_len(bytes.len());
+                encode_base64_into_slice(&mut buf[..len], bytes);
+                len
+            }
+        };
+        core::str::from_utf8(&buf[..len]).unwrap().serialize(s)
+    } else {
+        use serde::ser::SerializeTuple;
+
+        let mut seq = s.serialize_tuple(bytes.len())?;
+        for b in bytes {
+            seq.serialize_element(b)?;
+        }
+        seq.end()
+    }
+}
+
+fn deserialize_bytes<'de, D, T, F, const N: usize>(
+    d: D,
+    encoding: Encoding,
+    from_bytes: F,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    F: FnOnce(&[u8; N]) -> CtOption<T>,
+{
+    struct ByteArrayVisitor<const N: usize>(Encoding);
+
+    impl<'de, const N: usize> serde::de::Visitor<'de> for ByteArrayVisitor<N> {
+        type Value = [u8; N];
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            match self.0 {
+                Encoding::Hex => write!(f, "an array of {} bytes or a hex string", N),
+                Encoding::Base64 => write!(f, "an array of {} bytes or a base64 string", N),
+            }
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<[u8; N], A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut arr = [0u8; N];
+            for (i, b) in arr.iter_mut().enumerate() {
+                *b = seq
+                    .next_element()?
+                    .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+            }
+            Ok(arr)
+        }
+
+        fn visit_str<E>(self, s: &str) -> Result<[u8; N], E>
+        where
+            E: serde::de::Error,
+        {
+            let mut arr = [0u8; N];
+            let res = match self.0 {
+                Encoding::Hex => hex::decode_to_slice(s, &mut arr).map_err(|_| ()),
+                Encoding::Base64 => decode_base64_into_slice(&mut arr, s.as_bytes()),
+            };
+            res.map_err(|_| serde::de::Error::invalid_value(serde::de::Unexpected::Str(s), &self))?;
+            Ok(arr)
+        }
+    }
+
+    let visitor = ByteArrayVisitor::<N>(encoding);
+    let arr = if d.is_human_readable() {
+        d.deserialize_str(visitor)?
+    } else {
+        d.deserialize_tuple(N, visitor)?
+    };
+    Option::from(from_bytes(&arr)).ok_or_else(|| {
+        serde::de::Error::invalid_value(serde::de::Unexpected::Bytes(&arr), &ExpectedValid(N))
+    })
+}
+
+/// Describes a valid element of `N` bytes for error messages.
+struct ExpectedValid(usize);
+
+impl serde::de::Expected for ExpectedValid {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "a valid encoding of {} bytes", self.0)
+    }
+}
+
+macro_rules! impl_serde_adapter {
+    ($trait:ident, $name:ident, $serfunc:expr, $deserfunc:expr, $encoding:expr) => {
+        impl $trait for $name {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                serialize_bytes(&$serfunc(self), $encoding, s)
+            }
+
+            fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                deserialize_bytes(d, $encoding, $deserfunc)
+            }
+        }
+    };
+}
+
+/// A helper trait for serializing group elements in uncompressed form, which
+/// is larger but avoids computing a square root when deserializing. As with
+/// the default this is hex when human-readable, and elements are checked to
+/// be on the curve and in the correct subgroup when deserializing.
+///
+/// ```
+/// use bls12_381_plus::{G1Affine, Uncompressed};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// pub struct TestStruct {
+///     #[serde(with = "Uncompressed")]
+///     point: G1Affine,
+/// }
+///
+/// let t = TestStruct { point: G1Affine::generator() };
+/// let ser = serde_json::to_string(&t).unwrap();
+/// assert_eq!(ser.len(), "{\"point\":\"\"}".len() + 192);
+///
+/// let t2: TestStruct = serde_json::from_str(&ser).unwrap();
+/// assert_eq!(t2.point, G1Affine::generator());
+/// ```
+pub trait Uncompressed: Sized {
+    /// Serialize element in uncompressed form
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserialize into element from uncompressed form
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl_serde_adapter!(
+    Uncompressed,
+    G1Affine,
+    G1Affine::to_uncompressed,
+    G1Affine::from_uncompressed,
+    Encoding::Hex
+);
+impl_serde_adapter!(
+    Uncompressed,
+    G1Projective,
+    G1Projective::to_uncompressed,
+    G1Projective::from_uncompressed,
+    Encoding::Hex
+);
+impl_serde_adapter!(
+    Uncompressed,
+    G2Affine,
+    G2Affine::to_uncompressed,
+    G2Affine::from_uncompressed,
+    Encoding::Hex
+);
+impl_serde_adapter!(
+    Uncompressed,
+    G2Projective,
+    G2Projective::to_uncompressed,
+    G2Projective::from_uncompressed,
+    Encoding::Hex
+);
+
+/// A helper trait for serializing group elements in uncompressed form,
+/// without checking that they are on the curve or in the correct subgroup when
+/// deserializing. This is the cheapest way to load elements, such as from a
+/// local cache. **This is dangerous to use unless you trust the data you are
+/// reading; otherwise, API invariants may be broken.** Please consider using
+/// [`Uncompressed`] instead.
+///
+/// As $\mathbb{G}_T$ elements are only ever checked to be canonically encoded,
+/// this is the same as the default serialization for [`Gt`](crate::Gt).
+pub trait Unchecked: Sized {
+    /// Serialize element in uncompressed form
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserialize into element from uncompressed form without validation
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl_serde_adapter!(
+    Unchecked,
+    G1Affine,
+    G1Affine::to_uncompressed,
+    G1Affine::from_uncompressed_unchecked,
+    Encoding::Hex
+);
+impl_serde_adapter!(
+    Unchecked,
+    G1Projective,
+    G1Projective::to_uncompressed,
+    G1Projective::from_uncompressed_unchecked,
+    Encoding::Hex
+);
+impl_serde_adapter!(
+    Unchecked,
+    G2Affine,
+    G2Affine::to_uncompressed,
+    G2Affine::from_uncompressed_unchecked,
+    Encoding::Hex
+);
+impl_serde_adapter!(
+    Unchecked,
+    G2Projective,
+    G2Projective::to_uncompressed,
+    G2Projective::from_uncompressed_unchecked,
+    Encoding::Hex
+);
+#[cfg(feature = "pairings")]
+impl_serde_adapter!(Unchecked, Gt, Gt::to_bytes, Gt::from_bytes, Encoding::Hex);
+
+/// A helper trait for serializing elements as padded base64 instead of hex
+/// when human-readable. Points are in compressed form, and the binary encoding
+/// is the same as the default.
+///
+/// ```
+/// use bls12_381_plus::{Base64, G1Affine};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// pub struct TestStruct {
+///     #[serde(with = "Base64")]
+///     point: G1Affine,
+/// }
+///
+/// let t = TestStruct { point: G1Affine::identity() };
+/// let ser = serde_json::to_string(&t).unwrap();
+/// assert_eq!(ser, "{\"point\":\"wAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA\"}");
+/// ```
+pub trait Base64: Sized {
+    /// Serialize element as base64 when human-readable
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserialize into element from base64 when human-readable
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl_serde_adapter!(
+    Base64,
+    G1Affine,
+    G1Affine::to_compressed,
+    G1Affine::from_compressed,
+    Encoding::Base64
+);
+impl_serde_adapter!(
+    Base64,
+    G1Projective,
+    G1Projective::to_compressed,
+    G1Projective::from_compressed,
+    Encoding::Base64
+);
+impl_serde_adapter!(
+    Base64,
+    G2Affine,
+    G2Affine::to_compressed,
+    G2Affine::from_compressed,
+    Encoding::Base64
+);
+impl_serde_adapter!(
+    Base64,
+    G2Projective,
+    G2Projective::to_compressed,
+    G2Projective::from_compressed,
+    Encoding::Base64
+);
+#[cfg(feature = "pairings")]
+impl_serde_adapter!(Base64, Gt, Gt::to_bytes, Gt::from_bytes, Encoding::Base64);
+
+#[cfg(all(test, feature = "pairings"))]
+mod test_types {
+    use super::*;
+
+    #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+    pub struct Points {
+        #[serde(with = "Uncompressed")]
+        pub a: G1Affine,
+        #[serde(with = "Unchecked")]
+        pub b: G2Projective,
+        #[serde(with = "Base64")]
+        pub c: G2Affine,
+        #[serde(with = "Base64")]
+        pub d: Gt,
+    }
+}
+
+#[cfg(feature = "pairings")]
+#[test]
+fn test_serde_adapters() {
+    use group::Group;
+    use test_types::Points;
+
+    let p = Points {
+        a: G1Affine::generator(),
+        b: G2Projective::GENERATOR.double(),
+        c: -G2Affine::generator(),
+        d: Gt::generator(),
+    };
+
+    let json = serde_json::to_string(&p).unwrap();
+    let v: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        v["a"].as_str().unwrap(),
+        hex::encode(G1Affine::generator().to_uncompressed())
+    );
+    assert_eq!(v["b"].as_str().unwrap().len(), 384);
+    assert_eq!(v["c"].as_str().unwrap().len(), base64_len(96));
+    assert_eq!(v["d"].as_str().unwrap().len(), base64_len(576));
+    assert_eq!(serde_json::from_str::<Points>(&json).unwrap(), p);
+
+    let bare = serde_bare::to_vec(&p).unwrap();
+    assert_eq!(bare.len(), 96 + 192 + 96 + 576);
+    assert_eq!(serde_bare::from_slice::<Points>(&bare).unwrap(), p);
+
+    // Unchecked accepts points which are not on the curve
+    let mut bytes = G2Affine::generator().to_uncompressed();
+    bytes[191] ^= 1;
+    let json = json.replace(v["b"].as_str().unwrap(), &hex::encode(bytes));
+    assert!(serde_json::from_str::<Points>(&json).is_ok());
+
+    // Uncompressed does not
+    let mut bytes = G1Affine::generator().to_uncompressed();
+    bytes[95] ^= 1;
+    let json = json.replace(v["a"].as_str().unwrap(), &hex::encode(bytes));
+    assert!(serde_json::from_str::<Points>(&json).is_err());
+}
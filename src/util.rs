@@ -1,6 +1,11 @@
+use crate::limb32;
+
 /// Compute a + b + carry, returning the result and the new carry over.
 #[inline(always)]
 pub const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    if limb32::ENABLED {
+        return limb32::adc(a, b, carry);
+    }
     let ret = (a as u128) + (b as u128) + (carry as u128);
     (ret as u64, (ret >> 64) as u64)
 }
@@ -8,6 +13,9 @@ pub const fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
 /// Compute a - (b + borrow), returning the result and the new borrow.
 #[inline(always)]
 pub const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    if limb32::ENABLED {
+        return limb32::sbb(a, b, borrow);
+    }
     let ret = (a as u128).wrapping_sub((b as u128) + ((borrow >> 63) as u128));
     (ret as u64, (ret >> 64) as u64)
 }
@@ -15,6 +23,9 @@ pub const fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
 /// Compute a + (b * c) + carry, returning the result and the new carry over.
 #[inline(always)]
 pub const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    if limb32::ENABLED {
+        return limb32::mac(a, b, c, carry);
+    }
     let ret = (a as u128) + ((b as u128) * (c as u128)) + (carry as u128);
     (ret as u64, (ret >> 64) as u64)
 }
@@ -54,6 +65,245 @@ pub const fn decode_hex_byte(bytes: [u8; 2]) -> u8 {
     result
 }
 
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// The length of the padded base64 encoding of `len` bytes.
+pub const fn base64_len(len: usize) -> usize {
+    len.div_ceil(3) * 4
+}
+
+/// Encode `bytes` as padded base64 into `buffer`, which must be exactly
+/// `base64_len(bytes.len())` long.
+pub fn encode_base64_into_slice(buffer: &mut [u8], bytes: &[u8]) {
+    debug_assert_eq!(buffer.len(), base64_len(bytes.len()));
+    for (chunk, out) in bytes.chunks(3).zip(buffer.chunks_exact_mut(4)) {
+        let mut tmp = [0u8; 3];
+        tmp[..chunk.len()].copy_from_slice(chunk);
+        let n = u32::from_be_bytes([0, tmp[0], tmp[1], tmp[2]]);
+        for (i, o) in out.iter_mut().enumerate() {
+            *o = if i <= chunk.len() {
+                BASE64_ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize]
+            } else {
+                b'='
+            };
+        }
+    }
+}
+
+/// Decode padded base64 `bytes` into `buffer`, failing unless `bytes` is the
+/// canonical encoding of exactly `buffer.len()` bytes.
+pub fn decode_base64_into_slice(buffer: &mut [u8], bytes: &[u8]) -> Result<(), ()> {
+    fn decode_char(c: u8) -> Result<u32, ()> {
+        match c {
+            b'A'..=b'Z' => Ok((c - b'A') as u32),
+            b'a'..=b'z' => Ok((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Ok((c - b'0' + 52) as u32),
+            b'+' => Ok(62),
+            b'/' => Ok(63),
+            _ => Err(()),
+        }
+    }
+
+    if bytes.len() != base64_len(buffer.len()) {
+        return Err(());
+    }
+    for (out, chunk) in buffer.chunks_mut(3).zip(bytes.chunks_exact(4)) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            n <<= 6;
+            if i <= out.len() {
+                n |= decode_char(*c)?;
+            } else if *c != b'=' {
+                return Err(());
+            }
+        }
+        let n = n.to_be_bytes();
+        // Reject nonzero bits in the final character, which make the
+        // encoding non-canonical
+        if n[1 + out.len()..].iter().any(|b| *b != 0) {
+            return Err(());
+        }
+        out.copy_from_slice(&n[1..1 + out.len()]);
+    }
+    Ok(())
+}
+
+/// The number of elements inverted together by [`batch_invert`] without an
+/// allocator, bounding the scratch space kept on the stack.
+#[cfg(not(feature = "alloc"))]
+const BATCH_INVERT_CHUNK: usize = 16;
+
+/// Inverts every nonzero element of `values` in place with Montgomery's trick,
+/// leaving zeros unchanged. This is constant time with respect to the values.
+/// With the `alloc` feature this uses a single inversion, otherwise one per
+/// chunk of [`BATCH_INVERT_CHUNK`] elements.
+pub(crate) fn batch_invert<F: ff::Field>(values: &mut [F]) {
+    #[cfg(feature = "alloc")]
+    batch_invert_with_scratch(values, &mut alloc::vec![F::ONE; values.len()]);
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let mut scratch = [F::ONE; BATCH_INVERT_CHUNK];
+        for chunk in values.chunks_mut(BATCH_INVERT_CHUNK) {
+            batch_invert_with_scratch(chunk, &mut scratch);
+        }
+    }
+}
+
+/// The same as [`batch_invert`], but keeping the running products in
+/// `scratch` so that all of `values` share a single inversion.
+///
+/// Panics if `scratch` is shorter than `values`.
+pub(crate) fn batch_invert_with_scratch<F: ff::Field>(values: &mut [F], scratch: &mut [F]) {
+    assert!(
+        scratch.len() >= values.len(),
+        "the scratch space is shorter than the values"
+    );
+    let prefix = &mut scratch[..values.len()];
+
+    // Compute the running product, skipping zeros
+    let mut acc = F::ONE;
+    for (p, v) in prefix.iter_mut().zip(values.iter()) {
+        *p = acc;
+        acc = F::conditional_select(&(acc * v), &acc, v.is_zero());
+    }
+
+    // acc is never zero, as zeros were skipped
+    let mut inv = acc.invert().unwrap();
+    for (p, v) in prefix.iter().zip(values.iter_mut()).rev() {
+        let is_zero = v.is_zero();
+        let next = F::conditional_select(&(inv * *v), &inv, is_zero);
+        *v = F::conditional_select(&(inv * p), &F::ZERO, is_zero);
+        inv = next;
+    }
+}
+
+/// The same as [`batch_invert`], but variable time with respect to which of
+/// the values are zero.
+pub(crate) fn batch_invert_vartime<F: ff::Field>(values: &mut [F]) {
+    #[cfg(feature = "alloc")]
+    batch_invert_vartime_with_scratch(values, &mut alloc::vec![F::ONE; values.len()]);
+
+    #[cfg(not(feature = "alloc"))]
+    {
+        let mut scratch = [F::ONE; BATCH_INVERT_CHUNK];
+        for chunk in values.chunks_mut(BATCH_INVERT_CHUNK) {
+            batch_invert_vartime_with_scratch(chunk, &mut scratch);
+        }
+    }
+}
+
+/// The same as [`batch_invert_with_scratch`], but variable time with respect
+/// to which of the values are zero.
+///
+/// Panics if `scratch` is shorter than `values`.
+pub(crate) fn batch_invert_vartime_with_scratch<F: ff::Field>(values: &mut [F], scratch: &mut [F]) {
+    assert!(
+        scratch.len() >= values.len(),
+        "the scratch space is shorter than the values"
+    );
+    let prefix = &mut scratch[..values.len()];
+
+    let mut acc = F::ONE;
+    for (p, v) in prefix.iter_mut().zip(values.iter()) {
+        *p = acc;
+        if !v.is_zero_vartime() {
+            acc *= v;
+        }
+    }
+
+    let mut inv = acc.invert().unwrap();
+    for (p, v) in prefix.iter().zip(values.iter_mut()).rev() {
+        if !v.is_zero_vartime() {
+            let next = inv * *v;
+            *v = inv * p;
+            inv = next;
+        }
+    }
+}
+
+/// Computes a square root with the constant-time Tonelli-Shanks algorithm of
+/// `ff::helpers::sqrt_tonelli_shanks`, for fields which do not implement
+/// `PrimeField`. Here `q - 1 = 2^s * t` for odd `t`, `tm1d2` is `(t - 1) / 2`
+/// and `root_of_unity` is a primitive `2^s`-th root of unity.
+#[cfg(feature = "groups")]
+pub(crate) fn sqrt_tonelli_shanks<F: ff::Field>(
+    f: &F,
+    s: u32,
+    tm1d2: &[u64],
+    root_of_unity: F,
+) -> subtle::CtOption<F> {
+    // w = self^((t - 1) // 2)
+    let w = f.pow_vartime(tm1d2);
+    sqrt_tonelli_shanks_from_w(f, w, s, root_of_unity)
+}
+
+/// The Tonelli-Shanks loop of [`sqrt_tonelli_shanks`], for callers which
+/// compute `w = f^((t - 1) / 2)` themselves, e.g. with an addition chain.
+pub(crate) fn sqrt_tonelli_shanks_from_w<F: ff::Field>(
+    f: &F,
+    w: F,
+    s: u32,
+    root_of_unity: F,
+) -> subtle::CtOption<F> {
+    use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+    let mut v = s;
+    let mut x = w * f;
+    let mut b = x * w;
+    let mut z = root_of_unity;
+
+    for max_v in (1..=s).rev() {
+        let mut k = 1;
+        let mut b2k = b.square();
+        let mut j_less_than_v: Choice = 1.into();
+
+        for j in 2..max_v {
+            let b2k_is_one = b2k.ct_eq(&F::ONE);
+            let squared = F::conditional_select(&b2k, &z, b2k_is_one).square();
+            b2k = F::conditional_select(&squared, &b2k, b2k_is_one);
+            let new_z = F::conditional_select(&z, &squared, b2k_is_one);
+            j_less_than_v &= !j.ct_eq(&v);
+            k = u32::conditional_select(&j, &k, b2k_is_one);
+            z = F::conditional_select(&z, &new_z, j_less_than_v);
+        }
+
+        let result = x * z;
+        x = F::conditional_select(&result, &x, b.ct_eq(&F::ONE));
+        z = z.square();
+        b *= z;
+        v = k;
+    }
+
+    subtle::CtOption::new(x, x.square().ct_eq(f))
+}
+
+/// Computes `Field::sqrt_ratio` for fields which do not implement
+/// `PrimeField`, where `nonsquare` is the nonsquare $G_S$.
+#[cfg(feature = "groups")]
+pub(crate) fn sqrt_ratio_generic<F: ff::Field>(
+    num: &F,
+    div: &F,
+    nonsquare: &F,
+) -> (subtle::Choice, F) {
+    // Exactly one of a and G_S * a is square unless a is zero, in which case
+    // both are, and the result must be false if num is nonzero.
+    let a = div.invert().unwrap_or(F::ZERO) * num;
+    let b = a * nonsquare;
+
+    let sqrt_a = a.sqrt();
+    let sqrt_b = b.sqrt();
+    let is_square = sqrt_a.is_some();
+    let sqrt = F::conditional_select(
+        &sqrt_b.unwrap_or(F::ZERO),
+        &sqrt_a.unwrap_or(F::ZERO),
+        is_square,
+    );
+
+    (is_square & !(div.is_zero() & !num.is_zero()), sqrt)
+}
+
 macro_rules! impl_add_binop_specify_output {
     ($lhs:ident, $rhs:ident, $output:ident) => {
         impl<'b> Add<&'b $rhs> for $lhs {
@@ -463,7 +713,7 @@ macro_rules! impl_from_bytes {
         #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
         #[cfg(feature = "alloc")]
         impl TryFrom<alloc::vec::Vec<u8>> for $name {
-            type Error = alloc::string::String;
+            type Error = crate::DecodingError;
 
             fn try_from(value: alloc::vec::Vec<u8>) -> Result<Self, Self::Error> {
                 Self::try_from(value.as_slice())
@@ -473,7 +723,7 @@ macro_rules! impl_from_bytes {
         #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
         #[cfg(feature = "alloc")]
         impl TryFrom<&alloc::vec::Vec<u8>> for $name {
-            type Error = alloc::string::String;
+            type Error = crate::DecodingError;
 
             fn try_from(value: &alloc::vec::Vec<u8>) -> Result<Self, Self::Error> {
                 Self::try_from(value.as_slice())
@@ -483,22 +733,85 @@ macro_rules! impl_from_bytes {
         #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
         #[cfg(feature = "alloc")]
         impl TryFrom<alloc::boxed::Box<[u8]>> for $name {
-            type Error = alloc::string::String;
+            type Error = crate::DecodingError;
 
             fn try_from(value: alloc::boxed::Box<[u8]>) -> Result<Self, Self::Error> {
                 Self::try_from(value.as_ref())
             }
         }
 
-        #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-        #[cfg(feature = "alloc")]
         impl TryFrom<&[u8]> for $name {
-            type Error = alloc::string::String;
+            type Error = crate::DecodingError;
 
             fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-                Option::<$name>::from($frombytesfunc(value)?)
-                    .ok_or_else(|| alloc::format!("Invalid bytes for {}", stringify!($name)))
+                $frombytesfunc(value)
             }
         }
     };
 }
+
+#[test]
+fn test_base64() {
+    let vectors: [(&[u8], &str); 5] = [
+        (b"", ""),
+        (b"f", "Zg=="),
+        (b"fo", "Zm8="),
+        (b"foo", "Zm9v"),
+        (b"foobar", "Zm9vYmFy"),
+    ];
+    for (bytes, encoded) in vectors.iter() {
+        let mut buf = [0u8; 8];
+        encode_base64_into_slice(&mut buf[..base64_len(bytes.len())], bytes);
+        assert_eq!(&buf[..encoded.len()], encoded.as_bytes());
+
+        let mut buf = [0u8; 6];
+        decode_base64_into_slice(&mut buf[..bytes.len()], encoded.as_bytes()).unwrap();
+        assert_eq!(&buf[..bytes.len()], *bytes);
+    }
+
+    let mut buf = [0u8; 1];
+    assert!(decode_base64_into_slice(&mut buf, b"Zh==").is_err());
+    assert!(decode_base64_into_slice(&mut buf, b"Zg=a").is_err());
+    assert!(decode_base64_into_slice(&mut buf, b"Z!==").is_err());
+    assert!(decode_base64_into_slice(&mut buf, b"Zm8=").is_err());
+}
+
+#[test]
+fn test_batch_invert() {
+    use crate::Scalar;
+    use ff::Field;
+    use rand_core::SeedableRng;
+
+    let mut rng = rand_xorshift::XorShiftRng::from_seed([33u8; 16]);
+    // More than one chunk without an allocator, with zeros in between
+    let values = (0..70)
+        .map(|i| {
+            if i % 7 == 0 {
+                Scalar::ZERO
+            } else {
+                Scalar::random(&mut rng)
+            }
+        })
+        .collect::<std::vec::Vec<_>>();
+    let expected = values
+        .iter()
+        .map(|v| v.invert().unwrap_or(Scalar::ZERO))
+        .collect::<std::vec::Vec<_>>();
+
+    let mut scratch = std::vec![Scalar::ZERO; 80];
+    let mut tmp = values.clone();
+    batch_invert(&mut tmp);
+    assert_eq!(tmp, expected);
+    let mut tmp = values.clone();
+    batch_invert_vartime(&mut tmp);
+    assert_eq!(tmp, expected);
+    let mut tmp = values.clone();
+    batch_invert_with_scratch(&mut tmp, &mut scratch);
+    assert_eq!(tmp, expected);
+    let mut tmp = values.clone();
+    batch_invert_vartime_with_scratch(&mut tmp, &mut scratch);
+    assert_eq!(tmp, expected);
+
+    batch_invert::<Scalar>(&mut []);
+    batch_invert_with_scratch::<Scalar>(&mut [], &mut []);
+}